@@ -1,4 +1,5 @@
 use common::config::AppConfig;
+use common::scheduler::SlotScheduler;
 use futures::future::join_all;
 use tokio::runtime::Builder;
 use tokio::task::JoinHandle;
@@ -27,16 +28,25 @@ fn main() {
         .unwrap();
 
     runtime.block_on(async {
-        let peer_handle = peer::power_module_peer(config.peer).await.unwrap();
-        let consumer_handle = consumer::power_module_consumer(config.nats).await.unwrap();
-        let db_handle = db::power_module_db(config.webserver, config.database)
+        let scheduler = SlotScheduler::new(config.scheduler);
+        let peer_ticks = scheduler.subscribe();
+        let db_ticks = scheduler.subscribe();
+
+        let peer_handle = peer::power_module_peer(config.peer, config.genesis.clone(), peer_ticks).await.unwrap();
+        let consumer_handle = consumer::power_module_consumer(config.nats, config.queue).await.unwrap();
+        let db_handle = db::power_module_db(config.webserver, config.database, config.genesis, db_ticks)
             .await
             .unwrap();
 
+        let scheduler_handle = tokio::spawn(async move {
+            scheduler.power_scheduler().await;
+        });
+
         let mut threads: Vec<JoinHandle<()>> = Vec::new();
         threads.extend(peer_handle);
         threads.extend(consumer_handle);
         threads.extend(db_handle);
+        threads.push(scheduler_handle);
 
         join_all(threads).await;
     });
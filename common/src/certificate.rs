@@ -0,0 +1,38 @@
+use crate::memory::db_queue::OrderedBlock;
+use crate::round::Round;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Vote {
+    pub voter: String,
+    pub round: Round,
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct QuorumCertificate {
+    pub epoch: u64,
+    pub view: u64,
+    pub digest: String,
+    pub votes: Vec<Vote>,
+}
+
+pub fn digest_of(blocks: &[OrderedBlock], members: &[String]) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&(members.len() as u64).to_le_bytes());
+    for member in members {
+        hasher.update(&(member.len() as u64).to_le_bytes());
+        hasher.update(member.as_bytes());
+    }
+    for ((hash, organization, payload), created_at, round) in blocks {
+        for part in [hash, organization, payload] {
+            hasher.update(&(part.len() as u64).to_le_bytes());
+            hasher.update(part.as_bytes());
+        }
+        hasher.update(&created_at.timestamp_micros().to_le_bytes());
+        hasher.update(&round.epoch.to_le_bytes());
+        hasher.update(&round.slot.to_le_bytes());
+    }
+    general_purpose::URL_SAFE_NO_PAD.encode(hasher.finalize().as_bytes())
+}
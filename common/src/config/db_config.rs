@@ -8,4 +8,5 @@ pub struct DbConfig {
     pub password: String,
     pub db_name: String,
     pub thread_pool: u32,
+    pub bootstrap_sync: bool,
 }
@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct GenesisConfig {
+    pub chain_id: String,
+    pub timestamp: DateTime<Utc>,
+
+    #[serde(default)]
+    pub validators: Vec<String>,
+
+    #[serde(default)]
+    pub validator_keys_path: String,
+}
\ No newline at end of file
@@ -2,6 +2,10 @@ pub mod peer_config;
 pub mod db_config;
 pub mod nats_config;
 pub mod webserver_config;
+pub mod scheduler_config;
+pub mod stake_policy_config;
+pub mod queue_config;
+pub mod genesis_config;
 
 use std::env;
 use config::{Config, File};
@@ -10,6 +14,9 @@ use std::error::Error;
 
 use {peer_config::PeerConfig, db_config::DbConfig, nats_config::NatsConfig};
 use crate::config::webserver_config::WebServerConfig;
+use crate::config::scheduler_config::{Phase, SchedulerConfig};
+use crate::config::queue_config::QueueConfig;
+use crate::config::genesis_config::GenesisConfig;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct AppConfig {
@@ -17,6 +24,9 @@ pub struct AppConfig {
     pub webserver: WebServerConfig,
     pub database: DbConfig,
     pub nats: NatsConfig,
+    pub scheduler: SchedulerConfig,
+    pub queue: QueueConfig,
+    pub genesis: GenesisConfig,
 }
 
 impl AppConfig {
@@ -31,9 +41,51 @@ impl AppConfig {
             .map_err(|e| format!("Invalid config format: {:?}", e))?;
 
         config.apply_environment();
+        config.validate()?;
         Ok(config)
     }
 
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if self.scheduler.slot_seconds == 0 {
+            return Err("Scheduler slot_seconds must be positive".into());
+        }
+        if !self.scheduler.phases.contains(&Phase::Maintenance) {
+            return Err("Scheduler phases must contain a maintenance slot".into());
+        }
+        if self.scheduler.phases.last() != Some(&Phase::Agreement) {
+            return Err("Scheduler phases must end with an agreement slot".into());
+        }
+
+        if self.peer.max_batch_blocks == 0 || self.peer.max_batch_bytes == 0 {
+            return Err("Peer batch limits must be positive".into());
+        }
+
+        if self.peer.view_timeout_ms == 0 {
+            return Err("Peer view timeout must be positive".into());
+        }
+
+        if self.queue.default_weight == 0 || self.queue.organizations.iter().any(|quota| quota.weight == 0) {
+            return Err("Queue weights must be positive".into());
+        }
+
+        if self.webserver.default_page_size == 0 || self.webserver.default_page_size > self.webserver.max_page_size {
+            return Err("Webserver needs 0 < default_page_size <= max_page_size".into());
+        }
+
+        if self.genesis.chain_id.trim().is_empty() {
+            return Err("Genesis chain_id must not be empty".into());
+        }
+
+        let stake_policy = &self.peer.stake_policy;
+        if stake_policy.min_stake < 0 || stake_policy.min_stake > stake_policy.max_stake {
+            return Err("Stake policy needs 0 <= min_stake <= max_stake".into());
+        }
+        if !(stake_policy.min_stake..=stake_policy.max_stake).contains(&stake_policy.initial_stake) {
+            return Err("Stake policy initial_stake must lie between min_stake and max_stake".into());
+        }
+        Ok(())
+    }
+
     fn apply_environment(&mut self) {
         // peer
         match env::var("PEER_HOST") {
@@ -106,6 +158,55 @@ impl AppConfig {
             Err(_) => {},
         }
 
+        match env::var("PEER_MAX_CLOCK_SKEW_MS") {
+            Ok(val) => {
+                self.peer.max_clock_skew_ms = val.parse::<u32>().expect("Invalid PEER_MAX_CLOCK_SKEW_MS");
+            }
+            Err(_) => {},
+        }
+
+        match env::var("PEER_MAX_BATCH_BLOCKS") {
+            Ok(val) => {
+                self.peer.max_batch_blocks = val.parse::<u32>().expect("Invalid PEER_MAX_BATCH_BLOCKS");
+            },
+            Err(_) => {},
+        }
+
+        match env::var("PEER_MAX_BATCH_BYTES") {
+            Ok(val) => {
+                self.peer.max_batch_bytes = val.parse::<u64>().expect("Invalid PEER_MAX_BATCH_BYTES");
+            },
+            Err(_) => {},
+        }
+
+        match env::var("PEER_VIEW_TIMEOUT_MS") {
+            Ok(val) => {
+                self.peer.view_timeout_ms = val.parse::<u32>().expect("Invalid PEER_VIEW_TIMEOUT_MS");
+            },
+            Err(_) => {},
+        }
+
+        match env::var("PEER_STAKE_POLICY") {
+            Ok(val) => {
+                self.peer.stake_policy.kind = val.clone();
+            },
+            Err(_) => {},
+        }
+
+        match env::var("PEER_STAKE_REWARD_POOL") {
+            Ok(val) => {
+                self.peer.stake_policy.reward_pool = val.parse::<i32>().expect("Invalid PEER_STAKE_REWARD_POOL");
+            },
+            Err(_) => {},
+        }
+
+        match env::var("PEER_STAKE_PENALTY_POOL") {
+            Ok(val) => {
+                self.peer.stake_policy.penalty_pool = val.parse::<i32>().expect("Invalid PEER_STAKE_PENALTY_POOL");
+            },
+            Err(_) => {},
+        }
+
         match env::var("PEER_KEYS_PATH") {
             Ok(val) => {
                 self.peer.keys_path = val.clone();
@@ -135,6 +236,13 @@ impl AppConfig {
             Err(_) => {},
         }
 
+        match env::var("WEB_MAX_PAGE_SIZE") {
+            Ok(val) => {
+                self.webserver.max_page_size = val.parse::<u32>().expect("Invalid WEB_MAX_PAGE_SIZE");
+            },
+            Err(_) => {},
+        }
+
         // database
         match env::var("DB_HOST") {
             Ok(val) => {
@@ -178,6 +286,13 @@ impl AppConfig {
             Err(_) => {},
         }
 
+        match env::var("DB_BOOTSTRAP_SYNC") {
+            Ok(val) => {
+                self.database.bootstrap_sync = val.parse::<bool>().expect("Invalid DB_BOOTSTRAP_SYNC");
+            },
+            Err(_) => {},
+        }
+
         // nats
         match env::var("NATS_HOST") {
             Ok(val) => {
@@ -213,6 +328,50 @@ impl AppConfig {
             },
             Err(_) => {},
         }
+
+        // scheduler
+        match env::var("SCHEDULER_SLOT_SECONDS") {
+            Ok(val) => {
+                self.scheduler.slot_seconds = val.parse::<u64>().expect("Invalid SCHEDULER_SLOT_SECONDS");
+            },
+            Err(_) => {},
+        }
+
+        match env::var("SCHEDULER_PHASES") {
+            Ok(val) => {
+                self.scheduler.phases = val
+                    .split(',')
+                    .map(|phase| phase.parse::<Phase>().expect("Invalid SCHEDULER_PHASES"))
+                    .collect();
+            },
+            Err(_) => {},
+        }
+
+        // genesis
+        match env::var("GENESIS_CHAIN_ID") {
+            Ok(val) => {
+                self.genesis.chain_id = val.clone();
+            },
+            Err(_) => {},
+        }
+
+        match env::var("GENESIS_VALIDATORS") {
+            Ok(val) => {
+                self.genesis.validators = val
+                    .split(',')
+                    .map(|validator| validator.trim().to_string())
+                    .filter(|validator| !validator.is_empty())
+                    .collect();
+            },
+            Err(_) => {},
+        }
+
+        match env::var("GENESIS_VALIDATOR_KEYS_PATH") {
+            Ok(val) => {
+                self.genesis.validator_keys_path = val.clone();
+            },
+            Err(_) => {},
+        }
         
     }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use serde::Deserialize;
+use crate::config::stake_policy_config::StakePolicyConfig;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct PeerConfig {
@@ -16,6 +17,12 @@ pub struct PeerConfig {
     
     pub peer_connections: u32,
     pub committee_size: u32,
+    pub max_clock_skew_ms: u32,
+    pub max_batch_blocks: u32,
+    pub max_batch_bytes: u64,
+    pub view_timeout_ms: u32,
     
     pub keys_path: String,
+
+    pub stake_policy: StakePolicyConfig,
 }
\ No newline at end of file
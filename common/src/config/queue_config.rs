@@ -0,0 +1,45 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct OrganizationQuota {
+    pub organization: String,
+    pub weight: u32,
+    pub max_depth: u32,
+    pub rate_per_minute: u32,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct QueueConfig {
+    pub default_weight: u32,
+    pub default_max_depth: u32,
+    pub default_rate_per_minute: u32,
+
+    #[serde(default)]
+    pub organizations: Vec<OrganizationQuota>,
+}
+
+impl QueueConfig {
+    pub fn quota_of(&self, organization: &str) -> OrganizationQuota {
+        self.organizations
+            .iter()
+            .find(|quota| quota.organization == organization)
+            .cloned()
+            .unwrap_or_else(|| OrganizationQuota {
+                organization: organization.to_string(),
+                weight: self.default_weight,
+                max_depth: self.default_max_depth,
+                rate_per_minute: self.default_rate_per_minute,
+            })
+    }
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        QueueConfig {
+            default_weight: 1,
+            default_max_depth: u32::MAX,
+            default_rate_per_minute: u32::MAX,
+            organizations: Vec::new(),
+        }
+    }
+}
\ No newline at end of file
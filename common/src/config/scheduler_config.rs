@@ -0,0 +1,43 @@
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Maintenance,
+    Committee,
+    Sync,
+    Agreement,
+}
+
+impl FromStr for Phase {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "maintenance" => Ok(Phase::Maintenance),
+            "committee" => Ok(Phase::Committee),
+            "sync" => Ok(Phase::Sync),
+            "agreement" => Ok(Phase::Agreement),
+            other => Err(format!("Unknown phase {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Maintenance => write!(f, "maintenance"),
+            Phase::Committee => write!(f, "committee"),
+            Phase::Sync => write!(f, "sync"),
+            Phase::Agreement => write!(f, "agreement"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SchedulerConfig {
+    pub slot_seconds: u64,
+    pub phases: Vec<Phase>,
+}
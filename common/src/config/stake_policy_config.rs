@@ -0,0 +1,15 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct StakePolicyConfig {
+    pub kind: String,
+
+    pub initial_stake: i32,
+    pub min_stake: i32,
+    pub max_stake: i32,
+
+    pub reward_pool: i32,
+    pub penalty_pool: i32,
+    pub slash_penalty: i32,
+    pub penalize_committee: bool,
+}
\ No newline at end of file
@@ -5,4 +5,6 @@ pub struct WebServerConfig {
     pub host: String,
     pub port: u32,
     pub thread_pool: u32,
+    pub default_page_size: u32,
+    pub max_page_size: u32,
 }
\ No newline at end of file
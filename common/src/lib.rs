@@ -1,4 +1,8 @@
+pub mod certificate;
 pub mod config;
 pub mod intro;
 pub mod memory;
+pub mod payload;
 pub mod logger;
+pub mod round;
+pub mod scheduler;
\ No newline at end of file
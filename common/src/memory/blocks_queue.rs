@@ -1,27 +1,195 @@
+use chrono::{DateTime, Duration, Utc};
 use once_cell::sync::Lazy;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use crate::config::queue_config::QueueConfig;
+use crate::payload;
 
 pub struct BlocksQueue {
-    memory: VecDeque<(String, String, String, String)>,
+    memory: BTreeMap<String, VecDeque<(String, String)>>,
+    rotation: VecDeque<String>,
+    known: HashSet<String>,
+    committed: HashSet<String>,
+    unannounced: Vec<(String, String, String)>,
+
+    config: QueueConfig,
+    submissions: HashMap<String, VecDeque<DateTime<Utc>>>,
 }
 
 impl BlocksQueue {
     fn new() -> Self {
         BlocksQueue {
-            memory: VecDeque::new(),
+            memory: BTreeMap::new(),
+            rotation: VecDeque::new(),
+            known: HashSet::new(),
+            committed: HashSet::new(),
+            unannounced: Vec::new(),
+            config: QueueConfig::default(),
+            submissions: HashMap::new(),
+        }
+    }
+
+    pub fn set_config(&mut self, config: QueueConfig) {
+        self.config = config;
+    }
+
+    pub fn admit(&mut self, hash: &str, organization: &str, data: &str) -> Result<bool, String> {
+        if !payload::matches(hash, data) {
+            return Err(format!(
+                "Hash does not match payload, expected base64 blake3 digest {}",
+                payload::payload_hash(data)
+            ));
+        }
+        if self.known.contains(hash) {
+            return Ok(false);
+        }
+
+        let quota = self.config.quota_of(organization);
+        let depth = self.memory.get(organization).map(|queue| queue.len()).unwrap_or(0);
+        if depth >= quota.max_depth as usize {
+            return Err(format!("Queue depth quota of {} exceeded", quota.max_depth));
+        }
+
+        let now = Utc::now();
+        let submissions = self.submissions.entry(organization.to_string()).or_default();
+        while submissions.front().map(|at| now - *at > Duration::minutes(1)).unwrap_or(false) {
+            submissions.pop_front();
+        }
+        if submissions.len() >= quota.rate_per_minute as usize {
+            return Err(format!("Submission rate quota of {} per minute exceeded", quota.rate_per_minute));
+        }
+        submissions.push_back(now);
+
+        self.known.insert(hash.to_string());
+        Ok(true)
+    }
+
+    pub fn accept(&mut self, hash: String, organization: String, data: String) {
+        self.unannounced.push((hash.clone(), organization.clone(), data.clone()));
+        self.enqueue(hash, organization, data);
+    }
+
+    pub fn add_gossiped(&mut self, hash: String, organization: String, data: String) -> bool {
+        if !payload::matches(&hash, &data) || !self.known.insert(hash.clone()) {
+            return false;
+        }
+        self.enqueue(hash, organization, data);
+        true
+    }
+
+    pub fn restore(&mut self, hash: String, organization: String, data: String) {
+        if !self.known.insert(hash.clone()) {
+            return;
+        }
+        self.unannounced.push((hash.clone(), organization.clone(), data.clone()));
+        self.enqueue(hash, organization, data);
+    }
+
+    pub fn requeue(&mut self, hash: String, organization: String, data: String) -> bool {
+        if self.committed.contains(&hash) || self.holds(&(hash.clone(), organization.clone(), data.clone())) {
+            return false;
         }
+        self.known.insert(hash.clone());
+        self.enqueue(hash, organization, data);
+        true
     }
 
-    pub fn add_block(&mut self, hash: String, organization: String, feedback: String, data: String) {
-        self.memory.push_back((hash, organization, feedback, data));
+    pub fn reopen(&mut self, hash: String, organization: String, data: String) -> bool {
+        self.committed.remove(&hash);
+        self.requeue(hash, organization, data)
     }
 
-    pub fn get_block(&mut self) -> Option<(String, String, String, String)> {
-        self.memory.pop_front()
+    fn enqueue(&mut self, hash: String, organization: String, data: String) {
+        let queue = self.memory.entry(organization.clone()).or_default();
+        if queue.is_empty() {
+            self.rotation.push_back(organization);
+        }
+        queue.push_back((hash, data));
+    }
+
+    pub fn is_known(&self, hash: &str) -> bool {
+        self.known.contains(hash)
+    }
+
+    pub fn holds(&self, block: &(String, String, String)) -> bool {
+        let (hash, organization, data) = block;
+        self.memory
+            .get(organization)
+            .map(|queue| queue.iter().any(|(queued_hash, queued_data)| queued_hash == hash && queued_data == data))
+            .unwrap_or(false)
+    }
+
+    pub fn take_unannounced(&mut self) -> Vec<(String, String, String)> {
+        self.unannounced.drain(..).collect()
+    }
+
+    pub fn remove_committed(&mut self, hashes: &[String]) {
+        let committed: HashSet<&String> = hashes.iter().collect();
+        for queue in self.memory.values_mut() {
+            queue.retain(|(hash, _)| !committed.contains(hash));
+        }
+        self.memory.retain(|_, queue| !queue.is_empty());
+
+        let memory = &self.memory;
+        self.rotation.retain(|organization| memory.contains_key(organization));
+        self.known.extend(hashes.iter().cloned());
+        self.committed.extend(hashes.iter().cloned());
+    }
+
+    pub fn get_depths(&self) -> Vec<(String, usize)> {
+        self.memory.iter().map(|(organization, queue)| (organization.clone(), queue.len())).collect()
+    }
+
+    pub fn get_block(&mut self) -> Option<(String, String, String)> {
+        self.get_batch(1, u64::MAX).pop()
+    }
+
+    pub fn get_batch(&mut self, max_count: u32, max_bytes: u64) -> Vec<(String, String, String)> {
+        let mut batch = Vec::new();
+        let mut batch_bytes: u64 = 0;
+        let mut full = false;
+
+        while !full && batch.len() < max_count as usize {
+            let organization = match self.rotation.pop_front() {
+                Some(organization) => organization,
+                None => break,
+            };
+            let weight = self.config.quota_of(&organization).weight.max(1);
+            let queue = match self.memory.get_mut(&organization) {
+                Some(queue) => queue,
+                None => continue,
+            };
+
+            let mut taken = 0;
+            while taken < weight && batch.len() < max_count as usize {
+                let block_bytes = match queue.front() {
+                    Some((hash, data)) => (hash.len() + organization.len() + data.len()) as u64,
+                    None => break,
+                };
+                if !batch.is_empty() && batch_bytes + block_bytes > max_bytes {
+                    full = true;
+                    break;
+                }
+
+                let (hash, data) = queue.pop_front().unwrap();
+                batch_bytes += block_bytes;
+                batch.push((hash, organization.clone(), data));
+                taken += 1;
+            }
+
+            if queue.is_empty() {
+                self.memory.remove(&organization);
+            } else if taken == 0 {
+                self.rotation.push_front(organization);
+            } else {
+                self.rotation.push_back(organization);
+            }
+        }
+
+        batch
     }
 }
 
 pub static BLOCKS_QUEUE: Lazy<Arc<Mutex<BlocksQueue>>> =
-    Lazy::new(|| Arc::new(Mutex::new(BlocksQueue::new())));
+    Lazy::new(|| Arc::new(Mutex::new(BlocksQueue::new())));
\ No newline at end of file
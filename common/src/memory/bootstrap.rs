@@ -0,0 +1,38 @@
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub struct Bootstrap {
+    syncing: bool,
+    loaded_blocks: i64,
+}
+
+impl Bootstrap {
+    fn new() -> Self {
+        Bootstrap {
+            syncing: false,
+            loaded_blocks: 0,
+        }
+    }
+
+    pub fn begin(&mut self) {
+        self.syncing = true;
+        self.loaded_blocks = 0;
+    }
+
+    pub fn record_range(&mut self, blocks: i64) {
+        self.loaded_blocks += blocks;
+    }
+
+    pub fn finish(&mut self) -> i64 {
+        self.syncing = false;
+        self.loaded_blocks
+    }
+
+    pub fn is_syncing(&self) -> bool {
+        self.syncing
+    }
+}
+
+pub static BOOTSTRAP: Lazy<Arc<Mutex<Bootstrap>>> =
+    Lazy::new(|| Arc::new(Mutex::new(Bootstrap::new())));
\ No newline at end of file
@@ -0,0 +1,113 @@
+use crate::certificate::QuorumCertificate;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ChainBlock {
+    pub hash: String,
+    pub previous_hash: String,
+    pub organization: String,
+    pub payload: String,
+    pub created_at: DateTime<Utc>,
+    pub epoch: i64,
+    pub slot: i64,
+    #[serde(default)]
+    pub merkle_root: Option<String>,
+    #[serde(default)]
+    pub leaf_index: Option<i64>,
+    #[serde(default)]
+    pub height: Option<i64>,
+    #[serde(default)]
+    pub proposer: Option<String>,
+    #[serde(default)]
+    pub proposer_signature: Option<String>,
+    #[serde(default)]
+    pub committee: Option<Vec<String>>,
+    #[serde(default)]
+    pub simple_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChainRequest {
+    pub peer: String,
+    pub locator: Vec<String>,
+    pub limit: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChainResponse {
+    pub peer: String,
+    pub ancestor: String,
+    pub blocks: Vec<ChainBlock>,
+    pub complete: bool,
+    #[serde(default)]
+    pub certificates: Vec<QuorumCertificate>,
+}
+
+pub struct ChainSync {
+    outgoing_requests: Vec<ChainRequest>,
+    incoming_requests: Vec<ChainRequest>,
+    outgoing_responses: Vec<ChainResponse>,
+    incoming_responses: Vec<ChainResponse>,
+    relayed: HashSet<String>,
+}
+
+const RELAY_HISTORY: usize = 4096;
+
+impl ChainSync {
+    fn new() -> Self {
+        ChainSync {
+            outgoing_requests: Vec::new(),
+            incoming_requests: Vec::new(),
+            outgoing_responses: Vec::new(),
+            incoming_responses: Vec::new(),
+            relayed: HashSet::new(),
+        }
+    }
+
+    pub fn request(&mut self, request: ChainRequest) {
+        self.outgoing_requests.push(request);
+    }
+
+    pub fn take_outgoing_requests(&mut self) -> Vec<ChainRequest> {
+        self.outgoing_requests.drain(..).collect()
+    }
+
+    pub fn receive_request(&mut self, request: ChainRequest) {
+        self.incoming_requests.push(request);
+    }
+
+    pub fn take_incoming_requests(&mut self) -> Vec<ChainRequest> {
+        self.incoming_requests.drain(..).collect()
+    }
+
+    pub fn respond(&mut self, response: ChainResponse) {
+        self.outgoing_responses.push(response);
+    }
+
+    pub fn take_outgoing_responses(&mut self) -> Vec<ChainResponse> {
+        self.outgoing_responses.drain(..).collect()
+    }
+
+    pub fn receive_response(&mut self, response: ChainResponse) {
+        self.incoming_responses.push(response);
+    }
+
+    pub fn take_incoming_responses(&mut self) -> Vec<ChainResponse> {
+        self.incoming_responses.drain(..).collect()
+    }
+
+    pub fn mark_relayed(&mut self, id: String) -> bool {
+        if self.relayed.len() >= RELAY_HISTORY {
+            self.relayed.clear();
+        }
+        self.relayed.insert(id)
+    }
+}
+
+pub static CHAIN_SYNC: Lazy<Arc<Mutex<ChainSync>>> =
+    Lazy::new(|| Arc::new(Mutex::new(ChainSync::new())));
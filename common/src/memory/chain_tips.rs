@@ -0,0 +1,122 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ChainTip {
+    pub epoch: u64,
+    pub hash: String,
+    pub height: i64,
+    pub blocks: Vec<String>,
+
+    #[serde(default)]
+    pub certified_epochs: i64,
+}
+
+impl ChainTip {
+    pub fn outranks(&self, other: &ChainTip) -> bool {
+        self.certified_epochs
+            .cmp(&other.certified_epochs)
+            .then_with(|| self.height.cmp(&other.height))
+            .then_with(|| other.hash.cmp(&self.hash))
+            .is_gt()
+    }
+}
+
+pub struct ChainTips {
+    local: BTreeMap<u64, ChainTip>,
+    announced: Option<u64>,
+    peers: Vec<(String, ChainTip)>,
+    latest: BTreeMap<String, ChainTip>,
+    seen: HashSet<(String, u64)>,
+}
+
+const TIP_HISTORY: usize = 16;
+
+impl ChainTips {
+    fn new() -> Self {
+        ChainTips {
+            local: BTreeMap::new(),
+            announced: None,
+            peers: Vec::new(),
+            latest: BTreeMap::new(),
+            seen: HashSet::new(),
+        }
+    }
+
+    pub fn set_local(&mut self, tip: ChainTip) {
+        self.local.insert(tip.epoch, tip);
+        while self.local.len() > TIP_HISTORY {
+            let oldest = *self.local.keys().next().unwrap();
+            self.local.remove(&oldest);
+        }
+    }
+
+    pub fn get_local(&self) -> Option<ChainTip> {
+        self.local.values().last().cloned()
+    }
+
+    pub fn get_local_until(&self, epoch: u64) -> Option<ChainTip> {
+        self.local.range(..=epoch).next_back().map(|(_, tip)| tip.clone())
+    }
+
+    pub fn get_local_hashes(&self) -> Vec<String> {
+        self.local.values().rev().map(|tip| tip.hash.clone()).collect()
+    }
+
+    pub fn take_unannounced(&mut self) -> Option<ChainTip> {
+        let tip = self.get_local()?;
+        if self.announced == Some(tip.epoch) {
+            return None;
+        }
+        self.announced = Some(tip.epoch);
+        Some(tip)
+    }
+
+    pub fn has_seen(&self, url: &str, epoch: u64) -> bool {
+        self.seen.contains(&(url.to_string(), epoch))
+    }
+
+    pub fn add_peer_tip(&mut self, url: String, tip: ChainTip) -> bool {
+        if !self.seen.insert((url.clone(), tip.epoch)) {
+            return false;
+        }
+        let newer = self.latest.get(&url).map(|known| tip.epoch > known.epoch).unwrap_or(true);
+        if newer {
+            self.latest.insert(url.clone(), tip.clone());
+        }
+        self.peers.push((url, tip));
+        true
+    }
+
+    pub fn get_peer_tips(&self) -> Vec<(String, ChainTip)> {
+        self.latest.iter().map(|(url, tip)| (url.clone(), tip.clone())).collect()
+    }
+
+    pub fn take_forks(&mut self) -> Vec<(String, ChainTip, ChainTip)> {
+        let oldest = self.local.keys().next().copied().unwrap_or(0);
+        let newest = self.local.keys().last().copied().unwrap_or(0);
+
+        let mut forks = Vec::new();
+        let mut pending = Vec::new();
+        for (url, tip) in self.peers.drain(..) {
+            if tip.epoch > newest {
+                pending.push((url, tip));
+                continue;
+            }
+            if let Some(local) = self.local.get(&tip.epoch) {
+                if local.hash != tip.hash || local.height != tip.height {
+                    forks.push((url, local.clone(), tip));
+                }
+            }
+        }
+        self.peers = pending;
+        self.seen.retain(|(_, epoch)| *epoch >= oldest);
+        forks
+    }
+}
+
+pub static CHAIN_TIPS: Lazy<Arc<Mutex<ChainTips>>> =
+    Lazy::new(|| Arc::new(Mutex::new(ChainTips::new())));
@@ -0,0 +1,94 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct ClockSample {
+    pub offset_ms: i64,
+    pub rtt_ms: i64,
+    pub measured_at: DateTime<Utc>,
+}
+
+pub struct ClockSkew {
+    memory: HashMap<String, ClockSample>,
+    probes: HashMap<String, i64>,
+    tolerance_ms: i64,
+}
+
+impl ClockSkew {
+    fn new() -> Self {
+        ClockSkew {
+            memory: HashMap::new(),
+            probes: HashMap::new(),
+            tolerance_ms: i64::MAX,
+        }
+    }
+
+    pub fn set_tolerance(&mut self, tolerance_ms: i64) {
+        self.tolerance_ms = tolerance_ms;
+    }
+
+    pub fn get_tolerance(&self) -> i64 {
+        self.tolerance_ms
+    }
+
+    pub fn record(&mut self, url: String, offset_ms: i64, rtt_ms: i64) {
+        self.memory.insert(url, ClockSample {
+            offset_ms,
+            rtt_ms,
+            measured_at: Utc::now(),
+        });
+    }
+
+    pub fn probe(&mut self, url: String, sent_at: i64) {
+        self.probes.insert(url, sent_at);
+    }
+
+    pub fn answer_probe(&mut self, url: &str, sent_at: i64) -> bool {
+        if self.probes.get(url) != Some(&sent_at) {
+            return false;
+        }
+        self.probes.remove(url);
+        true
+    }
+
+    pub fn remove(&mut self, url: &str) {
+        self.memory.remove(url);
+        self.probes.remove(url);
+    }
+
+    pub fn get_samples(&self) -> Vec<(String, ClockSample)> {
+        let oldest = Utc::now() - Duration::minutes(5);
+        self.memory
+            .iter()
+            .filter(|(_, sample)| sample.measured_at >= oldest)
+            .map(|(url, sample)| (url.clone(), sample.clone()))
+            .collect()
+    }
+
+    // Positive when the peers are ahead of the local clock.
+    pub fn estimate(&self) -> Option<i64> {
+        let mut offsets: Vec<i64> = self
+            .get_samples()
+            .into_iter()
+            .map(|(_, sample)| sample.offset_ms)
+            .collect();
+        if offsets.is_empty() {
+            return None;
+        }
+        offsets.sort();
+        Some(offsets[offsets.len() / 2])
+    }
+
+    pub fn within_tolerance(&self) -> bool {
+        match self.estimate() {
+            Some(offset) => offset.abs() <= self.tolerance_ms,
+            None => true,
+        }
+    }
+}
+
+pub static CLOCK_SKEW: Lazy<Arc<Mutex<ClockSkew>>> =
+    Lazy::new(|| Arc::new(Mutex::new(ClockSkew::new())));
@@ -1,66 +1,128 @@
 use chrono::{DateTime, Utc};
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use crate::certificate::QuorumCertificate;
+use crate::round::Round;
+
+pub type BlockData = (String, String, String);
+pub type QueuedBlock = (Vec<BlockData>, DateTime<Utc>, Round);
+pub type OrderedBlock = (BlockData, DateTime<Utc>, Round);
+pub type EpochHeader = (String, String, Vec<String>, Option<QuorumCertificate>);
 
 pub struct DbQueue {
-    memory: HashMap<String, Vec<(Option<(String, String, String)>, DateTime<Utc>)>>,
+    memory: HashMap<String, Vec<QueuedBlock>>,
+    decisions: HashMap<u64, (Vec<OrderedBlock>, EpochHeader)>,
 }
 
 impl DbQueue {
     fn new() -> Self {
         DbQueue {
             memory: HashMap::new(),
+            decisions: HashMap::new(),
         }
     }
 
     pub fn add_block(
         &mut self,
         creator: String,
-        data: Option<(String, String, String)>,
+        data: Vec<BlockData>,
         created_at: DateTime<Utc>,
+        round: Round,
     ) {
         if self.memory.contains_key(&creator) {
             self.memory
                 .get_mut(&creator)
                 .unwrap()
-                .push((data, created_at));
+                .push((data, created_at, round));
         } else {
-            let mut stack: Vec<(Option<(String, String, String)>, DateTime<Utc>)> = Vec::new();
-            stack.push((data, created_at));
-            self.memory.insert(creator, stack);
+            self.memory.insert(creator, vec![(data, created_at, round)]);
         }
     }
 
-    pub fn get_last(&self, creator: &str) -> Option<&(Option<(String, String, String)>, DateTime<Utc>)> {
+    pub fn get_last(&self, creator: &str) -> Option<&QueuedBlock> {
         if let Some(data) = self.memory.get(creator) {
             return data.last();
         }
         None
     }
 
-    pub fn get_and_clear(&mut self) -> Vec<((String, String, String), DateTime<Utc>)> {
-        let memory_clone = self.memory.clone();
-        self.memory.clear();
+    pub fn remove_block(&mut self, creator: &str, round: Round) {
+        if let Some(stack) = self.memory.get_mut(creator) {
+            stack.retain(|(_, _, block_round)| *block_round != round);
+        }
+    }
 
-        let mut ordered_blocks: Vec<((String, String, String), DateTime<Utc>)> = memory_clone
+    pub fn get_epoch(&self, epoch: u64) -> Vec<OrderedBlock> {
+        let entries: Vec<QueuedBlock> = self
+            .memory
             .values()
-            .flat_map(|vec| vec.iter())
-            .filter_map(|(data_opt, created_at)| {
-                data_opt
-                    .as_ref()
-                    .map(|data| (data.clone(), *created_at))
+            .flat_map(|stack| stack.iter())
+            .filter(|(_, _, round)| round.epoch == epoch)
+            .cloned()
+            .collect();
+        Self::order(entries)
+    }
+
+    pub fn get_members(&self, epoch: u64) -> Vec<String> {
+        self.memory
+            .iter()
+            .filter(|(_, stack)| stack.iter().any(|(_, _, round)| round.epoch == epoch))
+            .map(|(creator, _)| creator.clone())
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect()
+    }
+
+    pub fn set_decision(&mut self, epoch: u64, blocks: Vec<OrderedBlock>, header: EpochHeader) {
+        self.decisions.insert(epoch, (blocks, header));
+    }
+
+    pub fn take_epoch(&mut self, epoch: u64) -> (Option<(Vec<OrderedBlock>, EpochHeader)>, Vec<BlockData>) {
+        let mut taken: Vec<QueuedBlock> = Vec::new();
+        for stack in self.memory.values_mut() {
+            let (kept, expired): (Vec<QueuedBlock>, Vec<QueuedBlock>) =
+                stack.drain(..).partition(|(_, _, round)| round.epoch > epoch);
+            *stack = kept;
+            taken.extend(expired);
+        }
+        self.memory.retain(|_, stack| !stack.is_empty());
+
+        let decision = self.decisions.remove(&epoch);
+        self.decisions.retain(|decided_epoch, _| *decided_epoch > epoch);
+
+        let decided: HashSet<&String> = match &decision {
+            Some((blocks, _)) => blocks.iter().map(|((hash, _, _), _, _)| hash).collect(),
+            None => HashSet::new(),
+        };
+        let undecided: Vec<BlockData> = Self::order(taken)
+            .into_iter()
+            .map(|(data, _, _)| data)
+            .filter(|(hash, _, _)| !decided.contains(hash))
+            .collect();
+        (decision, undecided)
+    }
+
+    fn order(entries: Vec<QueuedBlock>) -> Vec<OrderedBlock> {
+        let mut ordered_blocks: Vec<OrderedBlock> = entries
+            .into_iter()
+            .flat_map(|(batch, created_at, round)| {
+                batch.into_iter().map(move |data| (data, created_at, round))
             })
             .collect();
-        
-        ordered_blocks.sort_by(|((hash_a, _, _), created_at_a), ((hash_b, _, _), created_at_b)| {
-            created_at_a
-                .cmp(created_at_b)
+
+        ordered_blocks.sort_by(|((hash_a, _, _), created_at_a, round_a), ((hash_b, _, _), created_at_b, round_b)| {
+            round_a
+                .cmp(round_b)
+                .then_with(|| created_at_a.cmp(created_at_b))
                 .then_with(|| hash_a.cmp(hash_b))
         });
+
+        let mut included: HashSet<String> = HashSet::new();
+        ordered_blocks.retain(|((hash, _, _), _, _)| included.insert(hash.clone()));
         ordered_blocks
     }
 }
 
-pub static DB_QUEUE: Lazy<Arc<Mutex<DbQueue>>> = Lazy::new(|| Arc::new(Mutex::new(DbQueue::new())));
\ No newline at end of file
+pub static DB_QUEUE: Lazy<Arc<Mutex<DbQueue>>> = Lazy::new(|| Arc::new(Mutex::new(DbQueue::new())));
@@ -5,12 +5,14 @@ use std::collections::HashMap;
 
 pub struct FeedbackQueue {
     memory: HashMap<String, String>,
+    rejections: Vec<(String, String, String)>,
 }
 
 impl FeedbackQueue {
     fn new() -> Self {
         FeedbackQueue {
             memory: HashMap::new(),
+            rejections: Vec::new(),
         }
     }
 
@@ -18,9 +20,21 @@ impl FeedbackQueue {
         self.memory.insert(hash, feedback);
     }
 
+    pub fn peek_block(&self, hash: &str) -> Option<String> {
+        self.memory.get(hash).cloned()
+    }
+
     pub fn get_block(&mut self, hash: &str) -> Option<String> {
         self.memory.remove(hash)
     }
+
+    pub fn reject(&mut self, feedback: String, hash: String, reason: String) {
+        self.rejections.push((feedback, hash, reason));
+    }
+
+    pub fn take_rejections(&mut self) -> Vec<(String, String, String)> {
+        self.rejections.drain(..).collect()
+    }
 }
 
 pub static FEEDBACK_QUEUE: Lazy<Arc<Mutex<FeedbackQueue>>> =
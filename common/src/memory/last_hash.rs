@@ -1,19 +1,40 @@
+use once_cell::sync::OnceCell;
+
+pub const GENESIS_HASH: &str = "RGVmYXVsdEhhc2g";
+
+static GENESIS: OnceCell<String> = OnceCell::new();
+
+pub fn set_genesis(hash: String) {
+    let _ = GENESIS.set(hash);
+}
+
+pub fn genesis_hash() -> String {
+    GENESIS.get().cloned().unwrap_or_else(|| GENESIS_HASH.to_string())
+}
+
 pub struct LastHash {
     hash: String,
+    height: i64,
 }
 
 impl LastHash {
     pub fn new() -> LastHash {
         LastHash {
-            hash: "RGVmYXVsdEhhc2g".to_string(),
+            hash: genesis_hash(),
+            height: 0,
         }
     }
 
-    pub fn set(&mut self, hash: String) {
+    pub fn set(&mut self, hash: String, height: i64) {
         self.hash = hash;
+        self.height = height;
     }
 
     pub fn get(&self) -> String {
         self.hash.clone()
     }
+
+    pub fn get_height(&self) -> i64 {
+        self.height
+    }
 }
@@ -2,4 +2,10 @@ pub mod blocks_queue;
 pub mod db_queue;
 pub mod last_hash;
 pub mod lazy_clients;
-pub mod feedback_queue;
\ No newline at end of file
+pub mod feedback_queue;
+pub mod clock_skew;
+pub mod chain_tips;
+pub mod chain_sync;
+pub mod stake_table;
+pub mod pending_log;
+pub mod bootstrap;
\ No newline at end of file
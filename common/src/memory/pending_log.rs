@@ -0,0 +1,92 @@
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use tokio::sync::{watch, Mutex, Notify};
+
+#[derive(Debug, Clone)]
+pub enum PendingEvent {
+    Accepted {
+        hash: String,
+        organization: String,
+        payload: String,
+        feedback: Option<String>,
+    },
+    Delivered {
+        hash: String,
+    },
+}
+
+pub struct PendingLog {
+    events: Vec<PendingEvent>,
+    recorded: u64,
+    journaled: watch::Sender<u64>,
+    flush: Arc<Notify>,
+}
+
+impl PendingLog {
+    fn new() -> Self {
+        PendingLog {
+            events: Vec::new(),
+            recorded: 0,
+            journaled: watch::channel(0).0,
+            flush: Arc::new(Notify::new()),
+        }
+    }
+
+    pub fn record_accepted(&mut self, hash: String, organization: String, payload: String, feedback: Option<String>) -> u64 {
+        self.record(PendingEvent::Accepted {
+            hash,
+            organization,
+            payload,
+            feedback,
+        })
+    }
+
+    pub fn record_delivered(&mut self, hash: String) -> u64 {
+        self.record(PendingEvent::Delivered { hash })
+    }
+
+    fn record(&mut self, event: PendingEvent) -> u64 {
+        self.events.push(event);
+        self.recorded += 1;
+        self.flush.notify_one();
+        self.recorded
+    }
+
+    pub fn take_events(&mut self) -> (Vec<PendingEvent>, u64) {
+        (self.events.drain(..).collect(), self.recorded)
+    }
+
+    pub fn requeue(&mut self, mut events: Vec<PendingEvent>) {
+        events.append(&mut self.events);
+        self.events = events;
+    }
+
+    pub fn mark_journaled(&mut self, sequence: u64) {
+        self.journaled.send_if_modified(|journaled| {
+            if sequence <= *journaled {
+                return false;
+            }
+            *journaled = sequence;
+            true
+        });
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.journaled.subscribe()
+    }
+
+    pub fn flush_signal(&self) -> Arc<Notify> {
+        self.flush.clone()
+    }
+}
+
+pub async fn wait_journaled(mut journaled: watch::Receiver<u64>, sequence: u64) {
+    while *journaled.borrow_and_update() < sequence {
+        if journaled.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+pub static PENDING_LOG: Lazy<Arc<Mutex<PendingLog>>> =
+    Lazy::new(|| Arc::new(Mutex::new(PendingLog::new())));
\ No newline at end of file
@@ -0,0 +1,66 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use crate::round::Round;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StakeEntry {
+    pub url: String,
+    pub stake: i32,
+    pub reward: i32,
+    pub penalty: i32,
+    pub public_key: String,
+
+    #[serde(default)]
+    pub slashed: Vec<Round>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StakeSnapshot {
+    pub nodes: Vec<StakeEntry>,
+    pub seed: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommitteeRecord {
+    pub round: Round,
+    pub members: Vec<String>,
+    pub seed: String,
+}
+
+pub struct StakeTable {
+    snapshot: Option<StakeSnapshot>,
+    committees: Vec<CommitteeRecord>,
+    restored: Option<StakeSnapshot>,
+}
+
+impl StakeTable {
+    fn new() -> Self {
+        StakeTable {
+            snapshot: None,
+            committees: Vec::new(),
+            restored: None,
+        }
+    }
+
+    pub fn publish(&mut self, snapshot: StakeSnapshot, committee: CommitteeRecord) {
+        self.snapshot = Some(snapshot);
+        self.committees.push(committee);
+    }
+
+    pub fn take_pending(&mut self) -> (Option<StakeSnapshot>, Vec<CommitteeRecord>) {
+        (self.snapshot.take(), self.committees.drain(..).collect())
+    }
+
+    pub fn restore(&mut self, snapshot: StakeSnapshot) {
+        self.restored = Some(snapshot);
+    }
+
+    pub fn take_restored(&mut self) -> Option<StakeSnapshot> {
+        self.restored.take()
+    }
+}
+
+pub static STAKE_TABLE: Lazy<Arc<Mutex<StakeTable>>> =
+    Lazy::new(|| Arc::new(Mutex::new(StakeTable::new())));
\ No newline at end of file
@@ -0,0 +1,9 @@
+use base64::{engine::general_purpose, Engine as _};
+
+pub fn payload_hash(payload: &str) -> String {
+    general_purpose::STANDARD.encode(blake3::hash(payload.as_bytes()).as_bytes())
+}
+
+pub fn matches(hash: &str, payload: &str) -> bool {
+    payload_hash(payload) == hash
+}
\ No newline at end of file
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Round {
+    pub epoch: u64,
+    pub slot: u64,
+}
+
+impl Round {
+    pub fn new(epoch: u64, slot: u64) -> Round {
+        Round { epoch, slot }
+    }
+
+    pub fn previous_epoch(&self) -> u64 {
+        self.epoch.saturating_sub(1)
+    }
+
+    pub fn absolute(&self, slots_per_epoch: u64) -> u64 {
+        self.epoch * slots_per_epoch + self.slot
+    }
+}
+
+impl fmt::Display for Round {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "epoch={} slot={}", self.epoch, self.slot)
+    }
+}
@@ -0,0 +1,83 @@
+use crate::config::scheduler_config::{Phase, SchedulerConfig};
+use crate::logger::Logger;
+use crate::round::Round;
+use chrono::{DateTime, TimeZone, Utc};
+use tokio::sync::broadcast;
+use tokio::time::{sleep, Duration};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Tick {
+    pub round: Round,
+    pub phase: Phase,
+    pub at: DateTime<Utc>,
+    pub slots_per_epoch: u64,
+}
+
+pub struct SlotScheduler {
+    config: SchedulerConfig,
+    sender: broadcast::Sender<Tick>,
+}
+
+impl SlotScheduler {
+    pub fn new(config: SchedulerConfig) -> SlotScheduler {
+        let (sender, _) = broadcast::channel(16);
+        SlotScheduler { config, sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Tick> {
+        self.sender.subscribe()
+    }
+
+    fn slot_millis(&self) -> i64 {
+        self.config.slot_seconds as i64 * 1000
+    }
+
+    fn slots_per_epoch(&self) -> u64 {
+        self.config.phases.len() as u64
+    }
+
+    pub fn round_of(&self, absolute_slot: u64) -> Round {
+        Round {
+            epoch: absolute_slot / self.slots_per_epoch(),
+            slot: absolute_slot % self.slots_per_epoch(),
+        }
+    }
+
+    pub fn round_at(&self, time: DateTime<Utc>) -> Round {
+        let absolute_slot = (time.timestamp_millis().max(0) / self.slot_millis()) as u64;
+        self.round_of(absolute_slot)
+    }
+
+    pub fn phase_of(&self, round: Round) -> Phase {
+        self.config.phases[round.slot as usize]
+    }
+
+    pub async fn power_scheduler(&self) {
+        let slot_millis = self.slot_millis();
+        let mut last_slot: Option<i64> = None;
+        loop {
+            let now_millis = Utc::now().timestamp_millis();
+            let mut next_slot = now_millis.div_euclid(slot_millis) + 1;
+            if let Some(last) = last_slot {
+                next_slot = next_slot.max(last + 1);
+            }
+
+            let boundary_millis = next_slot * slot_millis;
+            let wait_millis = (boundary_millis - now_millis).max(0) as u64;
+            sleep(Duration::from_millis(wait_millis)).await;
+
+            let round = self.round_of(next_slot.max(0) as u64);
+            let tick = Tick {
+                round,
+                phase: self.phase_of(round),
+                at: Utc.timestamp_millis_opt(boundary_millis).unwrap(),
+                slots_per_epoch: self.slots_per_epoch(),
+            };
+            last_slot = Some(next_slot);
+
+            if self.sender.send(tick).is_err() {
+                Logger::console("scheduler", &format!("No subscribers for {}", round));
+            }
+        }
+    }
+}
@@ -3,6 +3,8 @@ use crate::blocks_loader::BLOCKS_LOADER;
 use common::config::nats_config::NatsConfig;
 use common::logger::Logger;
 use common::memory::blocks_queue::BLOCKS_QUEUE;
+use common::memory::feedback_queue::FEEDBACK_QUEUE;
+use common::memory::pending_log::{wait_journaled, PENDING_LOG};
 use futures::StreamExt;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::io;
@@ -93,7 +95,38 @@ pub async fn assembler(mut block_ready_receiver: Receiver<(String, String, Strin
         let block = block.unwrap();
 
         let mut blocks_queue = BLOCKS_QUEUE.lock().await;
-        blocks_queue.add_block(hash, organization, feedback, block);
+        let admitted = blocks_queue.admit(&hash, &organization, &block);
         drop(blocks_queue);
+
+        match admitted {
+            Ok(true) => {
+                let mut pending_log = PENDING_LOG.lock().await;
+                let sequence = pending_log.record_accepted(
+                    hash.clone(),
+                    organization.clone(),
+                    block.clone(),
+                    Some(feedback.clone()),
+                );
+                let journaled = pending_log.subscribe();
+                drop(pending_log);
+                wait_journaled(journaled, sequence).await;
+
+                let mut blocks_queue = BLOCKS_QUEUE.lock().await;
+                blocks_queue.accept(hash.clone(), organization, block);
+                drop(blocks_queue);
+
+                let mut feedback_queue = FEEDBACK_QUEUE.lock().await;
+                feedback_queue.add_block(hash, feedback);
+                drop(feedback_queue);
+            }
+            Ok(false) => {}
+            Err(reason) => {
+                Logger::console("consumer", &format!("Rejected {} from {}: {}", hash, organization, reason));
+
+                let mut feedback_queue = FEEDBACK_QUEUE.lock().await;
+                feedback_queue.reject(feedback, hash, reason);
+                drop(feedback_queue);
+            }
+        }
     }
 }
\ No newline at end of file
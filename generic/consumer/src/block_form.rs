@@ -3,6 +3,7 @@ use serde_json;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BlockForm {
+    /// Standard base64 of the blake3 digest of the assembled `data`; blocks with any other hash are rejected.
     pub hash: String,
     pub index: usize,
     pub total: usize,
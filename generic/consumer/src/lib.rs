@@ -5,14 +5,20 @@ mod tls_config;
 
 use rustls::crypto::aws_lc_rs::default_provider;
 use common::config::nats_config::NatsConfig;
+use common::config::queue_config::QueueConfig;
+use common::memory::blocks_queue::BLOCKS_QUEUE;
 use tokio::{io, task};
 use tokio::task::JoinHandle;
 use crate::architecture::start_consumer;
 
-pub async fn power_module_consumer(nats_config: NatsConfig) -> io::Result<Vec<JoinHandle<()>>> {
+pub async fn power_module_consumer(nats_config: NatsConfig, queue_config: QueueConfig) -> io::Result<Vec<JoinHandle<()>>> {
     default_provider()
         .install_default()
         .expect("failed to install default CryptoProvider");
+
+    let mut blocks_queue = BLOCKS_QUEUE.lock().await;
+    blocks_queue.set_config(queue_config);
+    drop(blocks_queue);
     
     let consumer_nats_config = nats_config.clone();
     
@@ -1,7 +1,13 @@
 use reqwest::Client;
 use serde::Serialize;
 use common::memory::feedback_queue::FEEDBACK_QUEUE;
+use common::memory::pending_log::PENDING_LOG;
+use common::logger::Logger;
 use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::time::{Duration, Instant};
+
+const MAX_DELIVERY_ATTEMPTS: u32 = 8;
+const RETRY_BASE_SECS: u64 = 2;
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -10,25 +16,109 @@ struct Payload<'a> {
     full_hash:  &'a str,
 }
 
-pub async fn start_notifier(mut hash_receiver: UnboundedReceiver<Vec<(String, String)>>) {
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rejection<'a> {
+    block_hash: &'a str,
+    rejected: bool,
+    reason: &'a str,
+}
+
+pub async fn start_rejection_notifier() {
     let client = Client::new();
-    
-    while let Some(hashes) = hash_receiver.recv().await {
+
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+
         let mut feedback_queue = FEEDBACK_QUEUE.lock().await;
-        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-        for (block_hash, full_hash) in hashes {
-            if let Some(url) = feedback_queue.get_block(&block_hash) {
-                let body = Payload {
-                    block_hash: &block_hash,
-                    full_hash:  &full_hash,
-                };
-                let _ = client
-                    .post(url)
-                    .json(&body)
-                    .send()
-                    .await;
-            }
-        }
+        let rejections = feedback_queue.take_rejections();
         drop(feedback_queue);
+
+        for (url, block_hash, reason) in rejections {
+            let body = Rejection {
+                block_hash: &block_hash,
+                rejected: true,
+                reason: &reason,
+            };
+            let _ = client
+                .post(url)
+                .json(&body)
+                .send()
+                .await;
+        }
     }
 }
+
+pub async fn start_notifier(mut hash_receiver: UnboundedReceiver<Vec<(String, String)>>) {
+    let client = Client::new();
+    let mut retries: Vec<(String, String, u32, Instant)> = Vec::new();
+
+    loop {
+        let due: Vec<(String, String, u32)> = tokio::select! {
+            received = hash_receiver.recv() => match received {
+                Some(hashes) => {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    hashes.into_iter().map(|(block_hash, full_hash)| (block_hash, full_hash, 0)).collect()
+                }
+                None => break,
+            },
+            _ = tokio::time::sleep(Duration::from_secs(1)) => {
+                let now = Instant::now();
+                let (due, waiting): (Vec<_>, Vec<_>) = retries.drain(..).partition(|(_, _, _, at)| *at <= now);
+                retries = waiting;
+                due.into_iter().map(|(block_hash, full_hash, attempts, _)| (block_hash, full_hash, attempts)).collect()
+            }
+        };
+
+        let mut delivered: Vec<String> = Vec::with_capacity(due.len());
+        for (block_hash, full_hash, attempts) in due {
+            let feedback_queue = FEEDBACK_QUEUE.lock().await;
+            let url = feedback_queue.peek_block(&block_hash);
+            drop(feedback_queue);
+
+            let url = match url {
+                Some(url) => url,
+                None => {
+                    delivered.push(block_hash);
+                    continue;
+                }
+            };
+            let body = Payload {
+                block_hash: &block_hash,
+                full_hash:  &full_hash,
+            };
+            let accepted = match client.post(url).json(&body).send().await {
+                Ok(response) => response.status().is_success(),
+                Err(_) => false,
+            };
+
+            if accepted {
+                let mut feedback_queue = FEEDBACK_QUEUE.lock().await;
+                feedback_queue.get_block(&block_hash);
+                drop(feedback_queue);
+                delivered.push(block_hash);
+                continue;
+            }
+
+            let attempts = attempts + 1;
+            if attempts >= MAX_DELIVERY_ATTEMPTS {
+                Logger::console(
+                    "notifier",
+                    &format!("Giving up on {} after {} attempts, kept for replay", block_hash, attempts),
+                );
+                let mut feedback_queue = FEEDBACK_QUEUE.lock().await;
+                feedback_queue.get_block(&block_hash);
+                drop(feedback_queue);
+                continue;
+            }
+            let backoff = Duration::from_secs(RETRY_BASE_SECS << attempts);
+            retries.push((block_hash, full_hash, attempts, Instant::now() + backoff));
+        }
+
+        let mut pending_log = PENDING_LOG.lock().await;
+        for block_hash in delivered {
+            pending_log.record_delivered(block_hash);
+        }
+        drop(pending_log);
+    }
+}
\ No newline at end of file
@@ -1,52 +1,107 @@
-use chrono::Utc;
 use tokio::sync::mpsc::UnboundedSender;
-use tokio::time::{sleep, Duration};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
 use common::logger::Logger;
-use common::memory::db_queue::DB_QUEUE;
+use common::memory::blocks_queue::BLOCKS_QUEUE;
+use common::memory::bootstrap::BOOTSTRAP;
+use common::memory::db_queue::{EpochHeader, DB_QUEUE};
+use common::memory::chain_tips::{ChainTip, CHAIN_TIPS};
+use common::memory::chain_sync::{ChainRequest, ChainResponse, CHAIN_SYNC};
+use common::memory::last_hash::genesis_hash;
 use common::memory::lazy_clients::LAZY_CLIENTS;
+use common::memory::stake_table::STAKE_TABLE;
+use common::scheduler::Tick;
+use common::config::scheduler_config::Phase;
 use crate::service::alter_service::AlterService;
 
 pub struct Synchronizer {
     pub(crate) alter_service: AlterService,
     pub(crate) feedback_sender: UnboundedSender<Vec<(String, String)>>,
+    pub(crate) resolving: Option<(String, ChainTip, u64)>,
+    pub(crate) branch: Option<ChainResponse>,
+    pub(crate) bootstrapping: Option<(String, u64)>,
+    pub(crate) unconfirmed: Option<ChainResponse>,
+    pub(crate) bootstrap_since: Option<u64>,
+    pub(crate) bootstrap_ranges: usize,
 }
 
+const BRANCH_LIMIT: i64 = 500;
+const BOOTSTRAP_GRACE_EPOCHS: u64 = 3;
+
 impl Synchronizer {
     
-    pub async fn power_synchronizer(&mut self) {
-        let mut last_seconds: i64 = 0;
+    pub async fn power_synchronizer(&mut self, mut ticks: broadcast::Receiver<Tick>) {
+        if let Some(snapshot) = self.alter_service.restore_stakes().await {
+            let mut stake_table = STAKE_TABLE.lock().await;
+            stake_table.restore(snapshot);
+            drop(stake_table);
+        }
+
         loop {
-            let now = Utc::now();
-            let seconds = now.timestamp() % 12;
-            let nanos = now.timestamp_subsec_nanos();
-
-            let remaining_secs = (12 - seconds) % 12;
-            let total_nanos = remaining_secs * 1_000_000_000 - nanos as i64;
-            let wait_duration = Duration::from_nanos(total_nanos.max(0) as u64);
-
-            let safe_sleep = wait_duration.saturating_sub(Duration::from_millis(200));
-            sleep(safe_sleep).await;
-
-            loop {
-                let now = Utc::now();
-                if now.timestamp() % 12 == 0 && last_seconds < now.timestamp() {
-                    last_seconds = now.timestamp();
-                    break;
+            let tick = match ticks.recv().await {
+                Ok(tick) => tick,
+                Err(RecvError::Lagged(skipped)) => {
+                    Logger::console("db", &format!("Missed {} ticks", skipped));
+                    continue;
                 }
-            }
+                Err(RecvError::Closed) => break,
+            };
+
+            let round = tick.round;
+            let bootstrap = BOOTSTRAP.lock().await;
+            let syncing = bootstrap.is_syncing();
+            drop(bootstrap);
 
-            let epoch = now.timestamp() % 60;
-            if epoch == 0 {
+            if tick.phase == Phase::Maintenance {
+                let committed_epoch = round.previous_epoch();
                 let mut db_queue = DB_QUEUE.lock().await;
-                let db_data = db_queue.get_and_clear();
+                let (db_data, undecided) = db_queue.take_epoch(committed_epoch);
                 drop(db_queue);
+
+                if syncing {
+                    Logger::console("bootstrap", &format!("epoch={} left to range sync", committed_epoch));
+                    self.serve_chain_requests().await;
+                    self.apply_chain_responses(round.epoch).await;
+                    self.bootstrap_step(round.epoch).await;
+                    continue;
+                }
+
+                let (db_data, header) = match db_data {
+                    Some(decision) => decision,
+                    None => {
+                        Logger::console("db", &format!("epoch={} has no agreed block order", committed_epoch));
+                        (Vec::new(), EpochHeader::default())
+                    }
+                };
+
+                let mut blocks_queue = BLOCKS_QUEUE.lock().await;
+                let requeued = undecided
+                    .into_iter()
+                    .filter(|(hash, organization, data)| {
+                        blocks_queue.requeue(hash.clone(), organization.clone(), data.clone())
+                    })
+                    .count();
+                drop(blocks_queue);
+                if requeued > 0 {
+                    Logger::console("db", &format!("epoch={} requeued {} undecided blocks", committed_epoch, requeued));
+                }
                 
-                let print_data: Vec<String> = db_data.iter().map(|((hash, _, _), _)| {
+                let print_data: Vec<String> = db_data.iter().map(|((hash, _, _), _, _)| {
                     hash.clone()
                 }).collect();
-                Logger::console("db", &format!("{:?}", print_data));
+                Logger::console("db", &format!("epoch={} {:?}", committed_epoch, print_data));
                 
-                let created_hashes = self.alter_service.create_blocks(db_data).await;
+                let created_hashes = self.alter_service.create_blocks(db_data, header).await;
+
+                let committed_blocks: Vec<String> = created_hashes.iter().map(|(_, full_hash)| {
+                    full_hash.clone()
+                }).collect();
+                let tip = self.alter_service.current_tip(committed_epoch, committed_blocks).await;
+                Logger::console("db", &format!("epoch={} tip={} height={}", committed_epoch, tip.hash, tip.height));
+
+                let mut chain_tips = CHAIN_TIPS.lock().await;
+                chain_tips.set_local(tip);
+                drop(chain_tips);
                 
                 let mut lazy_clients = LAZY_CLIENTS.lock().await;
                 let network = lazy_clients.get();
@@ -57,9 +112,268 @@ impl Synchronizer {
                 self.feedback_sender.send(created_hashes).unwrap();
             }
 
-            sleep(Duration::from_millis(1)).await;
+            let mut chain_tips = CHAIN_TIPS.lock().await;
+            let forks = chain_tips.take_forks();
+            drop(chain_tips);
+
+            self.alter_service.record_forks(&forks).await;
+
+            let mut stake_table = STAKE_TABLE.lock().await;
+            let (snapshot, committees) = stake_table.take_pending();
+            drop(stake_table);
+
+            self.alter_service.persist_stakes(snapshot, committees).await;
+
+            if syncing {
+                self.serve_chain_requests().await;
+                self.apply_chain_responses(round.epoch).await;
+                self.bootstrap_step(round.epoch).await;
+                continue;
+            }
+
+            self.choose_branch(forks, round.epoch).await;
+            self.serve_chain_requests().await;
+            self.apply_chain_responses(round.epoch).await;
         }
     }
-    
-    
+
+    async fn bootstrap_step(&mut self, epoch: u64) {
+        if let Some((_, requested_epoch)) = &self.bootstrapping {
+            if epoch <= requested_epoch + 1 {
+                return;
+            }
+            Logger::console("bootstrap", "Range request timed out");
+            self.bootstrapping = None;
+            self.unconfirmed = None;
+            self.bootstrap_ranges += 1;
+        }
+
+        let chain_tips = CHAIN_TIPS.lock().await;
+        let peer_tips = chain_tips.get_peer_tips();
+        drop(chain_tips);
+
+        let local_height = self.alter_service.last_hash.get_height();
+        let ahead: Vec<String> = peer_tips
+            .iter()
+            .filter(|(_, tip)| tip.height > local_height)
+            .map(|(url, _)| url.clone())
+            .collect();
+
+        if ahead.is_empty() {
+            self.unconfirmed = None;
+            let since = *self.bootstrap_since.get_or_insert(epoch);
+            if peer_tips.is_empty() && epoch < since + BOOTSTRAP_GRACE_EPOCHS {
+                return;
+            }
+
+            let mut bootstrap = BOOTSTRAP.lock().await;
+            let loaded = bootstrap.finish();
+            drop(bootstrap);
+
+            let tip = self.alter_service.current_tip(epoch, Vec::new()).await;
+            Logger::console(
+                "bootstrap",
+                &format!("Loaded {} blocks, joining at tip={} height={}", loaded, tip.hash, tip.height),
+            );
+            let mut chain_tips = CHAIN_TIPS.lock().await;
+            chain_tips.set_local(tip);
+            drop(chain_tips);
+            return;
+        }
+
+        let candidates: Vec<String> = match &self.unconfirmed {
+            Some(unconfirmed) => ahead.into_iter().filter(|url| *url != unconfirmed.peer).collect(),
+            None => ahead,
+        };
+        if candidates.is_empty() || (self.unconfirmed.is_none() && candidates.len() < 2) {
+            Logger::console("bootstrap", "Waiting for a second peer ahead to cross-check ranges");
+            return;
+        }
+
+        let peer = candidates[self.bootstrap_ranges % candidates.len()].clone();
+        let mut chain_sync = CHAIN_SYNC.lock().await;
+        chain_sync.request(ChainRequest {
+            peer: peer.clone(),
+            locator: vec![self.alter_service.last_hash.get()],
+            limit: BRANCH_LIMIT,
+        });
+        drop(chain_sync);
+
+        self.bootstrapping = Some((peer, epoch));
+    }
+
+    async fn choose_branch(&mut self, forks: Vec<(String, ChainTip, ChainTip)>, epoch: u64) {
+        if let Some((_, _, requested_epoch)) = &self.resolving {
+            if epoch <= requested_epoch + 1 {
+                return;
+            }
+            Logger::console("fork", "Branch request timed out");
+            self.resolving = None;
+            self.branch = None;
+        }
+
+        let best = forks
+            .into_iter()
+            .filter(|(_, local, remote)| remote.outranks(local))
+            .max_by(|(_, _, a), (_, _, b)| {
+                if a.outranks(b) { std::cmp::Ordering::Greater } else { std::cmp::Ordering::Less }
+            });
+
+        if let Some((peer, _, remote)) = best {
+            Logger::console(
+                "fork",
+                &format!("Peer {} outranks local chain with {}@{}", peer, remote.hash, remote.height),
+            );
+            self.request_branch(peer, remote, epoch).await;
+        }
+    }
+
+    async fn request_branch(&mut self, peer: String, remote: ChainTip, epoch: u64) {
+        let locator = match self.branch.as_ref().and_then(|branch| branch.blocks.last()) {
+            Some(last) => vec![last.hash.clone()],
+            None => {
+                let chain_tips = CHAIN_TIPS.lock().await;
+                let mut locator = vec![self.alter_service.last_hash.get()];
+                locator.extend(chain_tips.get_local_hashes());
+                drop(chain_tips);
+                locator.push(genesis_hash());
+                locator.dedup();
+                locator
+            }
+        };
+
+        let mut chain_sync = CHAIN_SYNC.lock().await;
+        chain_sync.request(ChainRequest {
+            peer: peer.clone(),
+            locator,
+            limit: BRANCH_LIMIT,
+        });
+        drop(chain_sync);
+
+        self.resolving = Some((peer, remote, epoch));
+    }
+
+    async fn serve_chain_requests(&mut self) {
+        let mut chain_sync = CHAIN_SYNC.lock().await;
+        let requests = chain_sync.take_incoming_requests();
+        drop(chain_sync);
+
+        for request in requests {
+            if let Some(response) = self.alter_service.get_branch(&request).await {
+                let mut chain_sync = CHAIN_SYNC.lock().await;
+                chain_sync.respond(response);
+                drop(chain_sync);
+            }
+        }
+    }
+
+    async fn apply_chain_responses(&mut self, epoch: u64) {
+        let mut chain_sync = CHAIN_SYNC.lock().await;
+        let responses = chain_sync.take_incoming_responses();
+        drop(chain_sync);
+
+        for response in responses {
+            if let Some((peer, _)) = &self.bootstrapping {
+                if *peer == response.peer {
+                    self.bootstrapping = None;
+                    self.bootstrap_ranges += 1;
+
+                    let response = match self.unconfirmed.take() {
+                        None => {
+                            if !response.blocks.is_empty() {
+                                self.unconfirmed = Some(response);
+                            }
+                            continue;
+                        }
+                        Some(unconfirmed) => match cross_checked(unconfirmed, &response) {
+                            Some(range) => range,
+                            None => {
+                                Logger::console(
+                                    "bootstrap",
+                                    &format!("Range from {} does not match the one it was checked against", response.peer),
+                                );
+                                continue;
+                            }
+                        },
+                    };
+
+                    let peer = response.peer.clone();
+                    let count = response.blocks.len() as i64;
+                    if let Some(committed) = self.alter_service.append_range(response).await {
+                        let mut bootstrap = BOOTSTRAP.lock().await;
+                        bootstrap.record_range(count);
+                        drop(bootstrap);
+
+                        Logger::console(
+                            "bootstrap",
+                            &format!("Loaded {} blocks from {} up to height {}", count, peer, self.alter_service.last_hash.get_height()),
+                        );
+                        if !committed.is_empty() {
+                            let _ = self.feedback_sender.send(committed);
+                        }
+                    }
+                    continue;
+                }
+            }
+
+            let (peer, remote) = match &self.resolving {
+                Some((peer, remote, _)) if *peer == response.peer => (peer.clone(), remote.clone()),
+                _ => continue,
+            };
+
+            let branch = match self.branch.take() {
+                None => response,
+                Some(mut branch) => {
+                    let continues = branch.blocks.last().map(|block| block.hash == response.ancestor).unwrap_or(false);
+                    if !continues {
+                        Logger::console("fork", &format!("Branch chunk from {} does not continue at {}", peer, response.ancestor));
+                        self.resolving = None;
+                        continue;
+                    }
+                    branch.blocks.extend(response.blocks);
+                    branch.certificates.extend(response.certificates);
+                    branch.complete = response.complete;
+                    branch
+                }
+            };
+
+            if !branch.complete {
+                self.branch = Some(branch);
+                self.request_branch(peer, remote, epoch).await;
+                continue;
+            }
+            self.resolving = None;
+
+            let reaches_tip = branch.blocks.last().map(|block| block.hash == remote.hash).unwrap_or(false);
+            if !reaches_tip {
+                Logger::console("fork", &format!("Branch from {} does not reach {}", peer, remote.hash));
+                continue;
+            }
+
+            if self.alter_service.switch_branch(branch).await {
+                let tip = self.alter_service.current_tip(remote.epoch, remote.blocks).await;
+                let mut chain_tips = CHAIN_TIPS.lock().await;
+                chain_tips.set_local(tip);
+                drop(chain_tips);
+            }
+        }
+    }
+}
+
+fn cross_checked(mut first: ChainResponse, second: &ChainResponse) -> Option<ChainResponse> {
+    if first.ancestor != second.ancestor {
+        return None;
+    }
+    let agreed = first
+        .blocks
+        .iter()
+        .zip(&second.blocks)
+        .take_while(|(left, right)| left == right)
+        .count();
+    if agreed == 0 {
+        return None;
+    }
+    first.blocks.truncate(agreed);
+    first.complete = first.complete && agreed == second.blocks.len();
+    Some(first)
 }
\ No newline at end of file
@@ -1,10 +1,12 @@
 use crate::http::info_http::{AppState, InfoHttp};
 use crate::service::read_service::ReadService;
+use crate::service::status_service::StatusService;
+use crate::service::verify_service::VerifyService;
 use common::config::webserver_config::WebServerConfig;
 use common::logger::Logger;
 use std::sync::Arc;
 
-pub async fn start_server(webserver_config: WebServerConfig, read_service: ReadService) {
+pub async fn start_server(webserver_config: WebServerConfig, read_service: ReadService, verify_service: VerifyService) {
     let host = if webserver_config.host.to_lowercase() == "localhost" {
         format!("127.0.0.1:{}", webserver_config.port)
     } else {
@@ -13,6 +15,10 @@ pub async fn start_server(webserver_config: WebServerConfig, read_service: ReadS
 
     let state = Arc::new(AppState {
         read_service: Arc::new(read_service),
+        status_service: Arc::new(StatusService),
+        verify_service: Arc::new(verify_service),
+        default_page_size: webserver_config.default_page_size,
+        max_page_size: webserver_config.max_page_size,
     });
 
     let app = InfoHttp::new(state);
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tokio_postgres::Row;
+use common::memory::chain_sync::ChainBlock;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BlockDTO {
@@ -9,6 +10,15 @@ pub struct BlockDTO {
     pub organization: String,
     pub payload: String,
     pub created_at: DateTime<Utc>,
+    pub epoch: i64,
+    pub slot: i64,
+    pub merkle_root: Option<String>,
+    pub leaf_index: Option<i64>,
+    pub height: Option<i64>,
+    pub proposer: Option<String>,
+    pub proposer_signature: Option<String>,
+    pub committee: Option<Vec<String>>,
+    pub simple_hash: Option<String>,
 }
 
 
@@ -20,6 +30,53 @@ impl BlockDTO {
             organization: row.get("organization"),
             payload: row.get("payload"),
             created_at: row.get("created_at"),
+            epoch: row.get("epoch"),
+            slot: row.get("slot"),
+            merkle_root: row.get("merkle_root"),
+            leaf_index: row.get("leaf_index"),
+            height: row.get("height"),
+            proposer: row.get("proposer"),
+            proposer_signature: row.get("proposer_signature"),
+            committee: row.get("committee"),
+            simple_hash: row.get("simple_hash"),
+        }
+    }
+
+    pub fn from_chain_block(block: ChainBlock) -> Self {
+        BlockDTO {
+            hash: block.hash,
+            previous_hash: block.previous_hash,
+            organization: block.organization,
+            payload: block.payload,
+            created_at: block.created_at,
+            epoch: block.epoch,
+            slot: block.slot,
+            merkle_root: block.merkle_root,
+            leaf_index: block.leaf_index,
+            height: block.height,
+            proposer: block.proposer,
+            proposer_signature: block.proposer_signature,
+            committee: block.committee,
+            simple_hash: block.simple_hash,
+        }
+    }
+
+    pub fn to_chain_block(&self) -> ChainBlock {
+        ChainBlock {
+            hash: self.hash.clone(),
+            previous_hash: self.previous_hash.clone(),
+            organization: self.organization.clone(),
+            payload: self.payload.clone(),
+            created_at: self.created_at,
+            epoch: self.epoch,
+            slot: self.slot,
+            merkle_root: self.merkle_root.clone(),
+            leaf_index: self.leaf_index,
+            height: self.height,
+            proposer: self.proposer.clone(),
+            proposer_signature: self.proposer_signature.clone(),
+            committee: self.committee.clone(),
+            simple_hash: self.simple_hash.clone(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Row;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForkDTO {
+    pub epoch: i64,
+    pub peer: String,
+    pub local_hash: String,
+    pub local_height: i64,
+    pub peer_hash: String,
+    pub peer_height: i64,
+    pub local_only: Vec<String>,
+    pub peer_only: Vec<String>,
+    pub detected_at: DateTime<Utc>,
+}
+
+
+impl ForkDTO {
+    pub fn from_row(row: Row) -> Self {
+        ForkDTO {
+            epoch: row.get("epoch"),
+            peer: row.get("peer"),
+            local_hash: row.get("local_hash"),
+            local_height: row.get("local_height"),
+            peer_hash: row.get("peer_hash"),
+            peer_height: row.get("peer_height"),
+            local_only: row.get("local_only"),
+            peer_only: row.get("peer_only"),
+            detected_at: row.get("detected_at"),
+        }
+    }
+}
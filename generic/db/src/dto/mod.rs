@@ -1,3 +1,13 @@
 pub mod block_dto;
 pub mod message_dto;
-pub mod connection_dto;
\ No newline at end of file
+pub mod connection_dto;
+pub mod status_dto;
+pub mod fork_dto;
+pub mod rollback_dto;
+pub mod stake_dto;
+pub mod pending_dto;
+pub mod proof_dto;
+pub mod verify_dto;
+pub mod snapshot_dto;
+pub mod page_dto;
+pub mod search_dto;
\ No newline at end of file
@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageDTO<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
\ No newline at end of file
@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Row;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PendingBlockDTO {
+    pub hash: String,
+    pub organization: String,
+    pub payload: String,
+    pub feedback: Option<String>,
+    pub full_hash: Option<String>,
+    pub accepted_at: DateTime<Utc>,
+}
+
+
+impl PendingBlockDTO {
+    pub fn from_row(row: Row) -> Self {
+        PendingBlockDTO {
+            hash: row.get("hash"),
+            organization: row.get("organization"),
+            payload: row.get("payload"),
+            feedback: row.get("feedback"),
+            full_hash: row.get("full_hash"),
+            accepted_at: row.get("accepted_at"),
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use crate::utils::merkle::ProofStep;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProofDTO {
+    pub hash: String,
+    pub leaf_hash: String,
+    pub leaf_index: i64,
+    pub leaf_count: i64,
+    pub merkle_root: String,
+    pub path: Vec<ProofStep>,
+}
\ No newline at end of file
@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Row;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RollbackDTO {
+    pub ancestor_hash: String,
+    pub peer: String,
+    pub reverted_hashes: Vec<String>,
+    pub applied_hashes: Vec<String>,
+    pub reverted_blocks: String,
+    pub reverted_at: DateTime<Utc>,
+}
+
+
+impl RollbackDTO {
+    pub fn from_row(row: Row) -> Self {
+        RollbackDTO {
+            ancestor_hash: row.get("ancestor_hash"),
+            peer: row.get("peer"),
+            reverted_hashes: row.get("reverted_hashes"),
+            applied_hashes: row.get("applied_hashes"),
+            reverted_blocks: row.get("reverted_blocks"),
+            reverted_at: row.get("reverted_at"),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchFilter {
+    pub organization: Option<String>,
+    pub proposer: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub min_height: Option<i64>,
+    pub max_height: Option<i64>,
+    pub contains: Option<String>,
+    pub path: Option<String>,
+    pub order: SortOrder,
+}
\ No newline at end of file
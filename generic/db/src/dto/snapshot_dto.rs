@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::dto::block_dto::BlockDTO;
+use crate::dto::stake_dto::{CommitteeDTO, StakeDTO};
+
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub version: u32,
+    pub chain_id: String,
+    pub genesis_hash: String,
+    pub genesis_validators: Vec<String>,
+    pub genesis_created_at: DateTime<Utc>,
+    pub tip_hash: String,
+    pub tip_height: i64,
+    pub blocks: i64,
+    pub stakes: i64,
+    pub committees: i64,
+    pub seed: Option<String>,
+    pub exported_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SnapshotRecord {
+    Manifest(SnapshotManifest),
+    Block(BlockDTO),
+    Stake(StakeDTO),
+    Committee(CommitteeDTO),
+}
\ No newline at end of file
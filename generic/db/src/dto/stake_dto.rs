@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Row;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StakeDTO {
+    pub url: String,
+    pub stake: i32,
+    pub reward: i32,
+    pub penalty: i32,
+    pub public_key: String,
+    pub slashed: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommitteeDTO {
+    pub epoch: i64,
+    pub slot: i64,
+    pub members: Vec<String>,
+    pub seed: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+
+impl StakeDTO {
+    pub fn from_row(row: Row) -> Self {
+        StakeDTO {
+            url: row.get("url"),
+            stake: row.get("stake"),
+            reward: row.get("reward"),
+            penalty: row.get("penalty"),
+            public_key: row.get("public_key"),
+            slashed: row.get("slashed"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+}
+
+impl CommitteeDTO {
+    pub fn from_row(row: Row) -> Self {
+        CommitteeDTO {
+            epoch: row.get("epoch"),
+            slot: row.get("slot"),
+            members: row.get("members"),
+            seed: row.get("seed"),
+            recorded_at: row.get("recorded_at"),
+        }
+    }
+}
\ No newline at end of file
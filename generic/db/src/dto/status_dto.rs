@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeerClockDTO {
+    pub url: String,
+    pub offset_ms: i64,
+    pub rtt_ms: i64,
+    pub measured_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PendingDTO {
+    pub organization: String,
+    pub depth: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatusDTO {
+    pub clock_offset_ms: Option<i64>,
+    pub max_clock_skew_ms: i64,
+    pub within_tolerance: bool,
+    pub peers: Vec<PeerClockDTO>,
+    pub pending: Vec<PendingDTO>,
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyDTO {
+    pub start: String,
+    pub checked: i64,
+    pub derived: i64,
+    pub last_hash: String,
+    pub last_height: Option<i64>,
+    pub first_break: Option<String>,
+    pub break_reason: Option<String>,
+    pub missing_predecessors: Vec<String>,
+    pub duplicate_heights: Vec<i64>,
+    pub valid: bool,
+}
\ No newline at end of file
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 use crate::dto::block_dto::BlockDTO;
 use crate::dto::message_dto::MessageDTO;
+use crate::dto::proof_dto::ProofDTO;
 use crate::service::read_service::ReadService;
 use axum::{extract::State, Json, Router};
 use axum::extract::Query;
@@ -8,11 +9,28 @@ use axum::routing::get;
 use http::StatusCode;
 use serde::Deserialize;
 use crate::dto::connection_dto::ConnectionDTO;
+use crate::dto::status_dto::StatusDTO;
+use crate::dto::fork_dto::ForkDTO;
+use crate::dto::rollback_dto::RollbackDTO;
+use crate::dto::stake_dto::{CommitteeDTO, StakeDTO};
+use crate::service::status_service::StatusService;
+use crate::service::verify_service::VerifyService;
+use crate::dto::verify_dto::VerifyDTO;
 use crate::utils::cors_policy::CORS;
+use crate::dto::page_dto::PageDTO;
+use crate::dto::search_dto::{SearchFilter, SortOrder};
+use chrono::{DateTime, Utc};
+use deadpool_postgres::PoolError;
+use tokio_postgres::error::SqlState;
+use crate::utils::cursor::{self, Cursor};
 
 #[derive(Clone)]
 pub struct AppState {
     pub(crate) read_service: Arc<ReadService>,
+    pub(crate) status_service: Arc<StatusService>,
+    pub(crate) verify_service: Arc<VerifyService>,
+    pub(crate) default_page_size: u32,
+    pub(crate) max_page_size: u32,
 }
 
 #[derive(Deserialize)]
@@ -21,14 +39,83 @@ pub struct HashQuery {
 }
 
 #[derive(Deserialize)]
-pub struct OffsetQuery {
-    offset: i64,
+pub struct TraversalQuery {
+    hash: String,
+    depth: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct VerifyQuery {
+    hash: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct HeightQuery {
+    height: i64,
+}
+
+#[derive(Deserialize)]
+pub struct PageQuery {
+    cursor: Option<String>,
+    limit: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct EpochQuery {
+    epoch: i64,
 }
 
 #[derive(Deserialize)]
 pub struct OrganizationQuery {
     organization: String,
-    offset: i64,
+    cursor: Option<String>,
+    limit: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    organization: Option<String>,
+    proposer: Option<String>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    min_height: Option<i64>,
+    max_height: Option<i64>,
+    contains: Option<String>,
+    path: Option<String>,
+    order: Option<SortOrder>,
+    cursor: Option<String>,
+    limit: Option<u32>,
+}
+
+fn page_params(
+    state: &AppState,
+    cursor: Option<String>,
+    limit: Option<u32>,
+) -> Result<(Option<Cursor>, i64), (StatusCode, Json<MessageDTO>)> {
+    keyed_page_params(state, cursor, limit, cursor::decode)
+}
+
+fn keyed_page_params<C>(
+    state: &AppState,
+    cursor: Option<String>,
+    limit: Option<u32>,
+    decode: fn(&str) -> Option<C>,
+) -> Result<(Option<C>, i64), (StatusCode, Json<MessageDTO>)> {
+    let limit = limit
+        .unwrap_or(state.default_page_size)
+        .clamp(1, state.max_page_size) as i64;
+    match cursor {
+        None => Ok((None, limit)),
+        Some(raw) => match decode(&raw) {
+            Some(cursor) => Ok((Some(cursor), limit)),
+            None => Err((
+                StatusCode::BAD_REQUEST,
+                Json(MessageDTO {
+                    message: "Invalid Cursor".to_string(),
+                }),
+            )),
+        },
+    }
 }
 
 async fn get_by_hash(
@@ -52,11 +139,146 @@ async fn get_by_hash(
     }
 }
 
+async fn get_by_height(
+    Query(query): Query<HeightQuery>,
+    State(state): State<Arc<AppState>>
+) -> Result<Json<BlockDTO>, (StatusCode, Json<MessageDTO>)> {
+    match state.read_service.get_by_height(query.height).await {
+        Ok(Some(block)) => Ok(Json(block)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(MessageDTO {
+                message: "Not Found".to_string(),
+            }),
+        )),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(MessageDTO {
+                message: "Server Error".to_string(),
+            }),
+        )),
+    }
+}
+
+async fn get_successor(
+    Query(query): Query<HashQuery>,
+    State(state): State<Arc<AppState>>
+) -> Result<Json<BlockDTO>, (StatusCode, Json<MessageDTO>)> {
+    match state.read_service.get_successor(query.hash).await {
+        Ok(Some(block)) => Ok(Json(block)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(MessageDTO {
+                message: "Not Found".to_string(),
+            }),
+        )),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(MessageDTO {
+                message: "Server Error".to_string(),
+            }),
+        )),
+    }
+}
+
+async fn get_predecessor(
+    Query(query): Query<HashQuery>,
+    State(state): State<Arc<AppState>>
+) -> Result<Json<BlockDTO>, (StatusCode, Json<MessageDTO>)> {
+    match state.read_service.get_predecessor(query.hash).await {
+        Ok(Some(block)) => Ok(Json(block)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(MessageDTO {
+                message: "Not Found".to_string(),
+            }),
+        )),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(MessageDTO {
+                message: "Server Error".to_string(),
+            }),
+        )),
+    }
+}
+
+async fn get_ancestors(
+    Query(query): Query<TraversalQuery>,
+    State(state): State<Arc<AppState>>
+) -> Result<Json<Vec<BlockDTO>>, (StatusCode, Json<MessageDTO>)> {
+    let depth = query
+        .depth
+        .unwrap_or(state.default_page_size)
+        .clamp(1, state.max_page_size) as i64;
+    match state.read_service.get_ancestors(query.hash, depth).await {
+        Ok(Some(blocks)) => Ok(Json(blocks)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(MessageDTO {
+                message: "Not Found".to_string(),
+            }),
+        )),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(MessageDTO {
+                message: "Server Error".to_string(),
+            }),
+        )),
+    }
+}
+
+async fn get_descendants(
+    Query(query): Query<TraversalQuery>,
+    State(state): State<Arc<AppState>>
+) -> Result<Json<Vec<BlockDTO>>, (StatusCode, Json<MessageDTO>)> {
+    let depth = query
+        .depth
+        .unwrap_or(state.default_page_size)
+        .clamp(1, state.max_page_size) as i64;
+    match state.read_service.get_descendants(query.hash, depth).await {
+        Ok(Some(blocks)) => Ok(Json(blocks)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(MessageDTO {
+                message: "Not Found".to_string(),
+            }),
+        )),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(MessageDTO {
+                message: "Server Error".to_string(),
+            }),
+        )),
+    }
+}
+
+async fn get_proof(
+    Query(query): Query<HashQuery>,
+    State(state): State<Arc<AppState>>
+) -> Result<Json<ProofDTO>, (StatusCode, Json<MessageDTO>)> {
+    match state.read_service.get_proof(query.hash).await {
+        Ok(Some(proof)) => Ok(Json(proof)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(MessageDTO {
+                message: "Not Found".to_string(),
+            }),
+        )),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(MessageDTO {
+                message: "Server Error".to_string(),
+            }),
+        )),
+    }
+}
+
 async fn get_page(
-    Query(query): Query<OffsetQuery>,
+    Query(query): Query<PageQuery>,
     State(state): State<Arc<AppState>>,
-) -> Result<Json<Vec<BlockDTO>>, (StatusCode, Json<MessageDTO>)> {
-    match state.read_service.get_page(query.offset).await {
+) -> Result<Json<PageDTO<BlockDTO>>, (StatusCode, Json<MessageDTO>)> {
+    let (cursor, limit) = page_params(&state, query.cursor, query.limit)?;
+    match state.read_service.get_page(cursor, limit).await {
         Ok(blocks) => Ok(Json(blocks)),
         Err(_) => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -70,9 +292,10 @@ async fn get_page(
 async fn get_by_organisation(
     Query(query): Query<OrganizationQuery>,
     state: State<Arc<AppState>>,
-) -> Result<Json<Vec<BlockDTO>>, (StatusCode, Json<MessageDTO>)> {
+) -> Result<Json<PageDTO<BlockDTO>>, (StatusCode, Json<MessageDTO>)> {
+    let (cursor, limit) = page_params(&state, query.cursor, query.limit)?;
     match state.read_service
-        .get_by_organization(query.organization, query.offset)
+        .get_by_organization(query.organization, cursor, limit)
         .await
     {
         Ok(blocks) => Ok(Json(blocks)),
@@ -85,6 +308,52 @@ async fn get_by_organisation(
     }
 }
 
+async fn search(
+    Query(query): Query<SearchQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<PageDTO<BlockDTO>>, (StatusCode, Json<MessageDTO>)> {
+    let (cursor, limit) = page_params(&state, query.cursor, query.limit)?;
+    let contains_is_json = query
+        .contains
+        .as_ref()
+        .map(|contains| serde_json::from_str::<serde_json::Value>(contains).is_ok())
+        .unwrap_or(true);
+    if !contains_is_json {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(MessageDTO {
+                message: "Invalid Filter".to_string(),
+            }),
+        ));
+    }
+    let filter = SearchFilter {
+        organization: query.organization,
+        proposer: query.proposer,
+        from: query.from,
+        to: query.to,
+        min_height: query.min_height,
+        max_height: query.max_height,
+        contains: query.contains,
+        path: query.path,
+        order: query.order.unwrap_or_default(),
+    };
+    match state.read_service.search(filter, cursor, limit).await {
+        Ok(blocks) => Ok(Json(blocks)),
+        Err(PoolError::Backend(err)) if err.code() == Some(&SqlState::SYNTAX_ERROR) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(MessageDTO {
+                message: "Invalid Filter".to_string(),
+            }),
+        )),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(MessageDTO {
+                message: "Server Error".to_string(),
+            }),
+        )),
+    }
+}
+
 async fn get_network(state: State<Arc<AppState>>) -> Result<Json<Vec<ConnectionDTO>>, (StatusCode, Json<MessageDTO>)> {
     match state.read_service
         .get_connections()
@@ -100,15 +369,133 @@ async fn get_network(state: State<Arc<AppState>>) -> Result<Json<Vec<ConnectionD
     }
 }
 
+async fn get_forks(
+    Query(query): Query<PageQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<PageDTO<ForkDTO>>, (StatusCode, Json<MessageDTO>)> {
+    let (cursor, limit) = keyed_page_params(&state, query.cursor, query.limit, cursor::decode_sequence)?;
+    match state.read_service.get_forks(cursor, limit).await {
+        Ok(forks) => Ok(Json(forks)),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(MessageDTO {
+                message: "Server Error".to_string(),
+            }),
+        )),
+    }
+}
+
+async fn get_rollbacks(
+    Query(query): Query<PageQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<PageDTO<RollbackDTO>>, (StatusCode, Json<MessageDTO>)> {
+    let (cursor, limit) = keyed_page_params(&state, query.cursor, query.limit, cursor::decode_sequence)?;
+    match state.read_service.get_rollbacks(cursor, limit).await {
+        Ok(rollbacks) => Ok(Json(rollbacks)),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(MessageDTO {
+                message: "Server Error".to_string(),
+            }),
+        )),
+    }
+}
+
+async fn get_stakes(
+    Query(query): Query<PageQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<PageDTO<StakeDTO>>, (StatusCode, Json<MessageDTO>)> {
+    let (cursor, limit) = keyed_page_params(&state, query.cursor, query.limit, cursor::decode_key)?;
+    match state.read_service.get_stakes(cursor, limit).await {
+        Ok(stakes) => Ok(Json(stakes)),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(MessageDTO {
+                message: "Server Error".to_string(),
+            }),
+        )),
+    }
+}
+
+async fn get_committees(
+    Query(query): Query<PageQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<PageDTO<CommitteeDTO>>, (StatusCode, Json<MessageDTO>)> {
+    let (cursor, limit) = keyed_page_params(&state, query.cursor, query.limit, cursor::decode_pair)?;
+    match state.read_service.get_committees(cursor, limit).await {
+        Ok(committees) => Ok(Json(committees)),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(MessageDTO {
+                message: "Server Error".to_string(),
+            }),
+        )),
+    }
+}
+
+async fn get_committees_by_epoch(
+    Query(query): Query<EpochQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<CommitteeDTO>>, (StatusCode, Json<MessageDTO>)> {
+    match state.read_service.get_committees_by_epoch(query.epoch).await {
+        Ok(committees) => Ok(Json(committees)),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(MessageDTO {
+                message: "Server Error".to_string(),
+            }),
+        )),
+    }
+}
+
+async fn verify_chain(
+    Query(query): Query<VerifyQuery>,
+    State(state): State<Arc<AppState>>
+) -> Result<Json<VerifyDTO>, (StatusCode, Json<MessageDTO>)> {
+    match state.verify_service.verify(query.hash).await {
+        Ok(Some(report)) => Ok(Json(report)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(MessageDTO {
+                message: "Not Found".to_string(),
+            }),
+        )),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(MessageDTO {
+                message: "Server Error".to_string(),
+            }),
+        )),
+    }
+}
+
+async fn get_status(state: State<Arc<AppState>>) -> Json<StatusDTO> {
+    Json(state.status_service.get_status().await)
+}
+
 pub struct InfoHttp;
 
 impl InfoHttp {
     pub fn new(state: Arc<AppState>) -> Router {
         Router::new()
             .route("/row", get(get_by_hash))
+            .route("/height", get(get_by_height))
+            .route("/next", get(get_successor))
+            .route("/previous", get(get_predecessor))
+            .route("/ancestors", get(get_ancestors))
+            .route("/descendants", get(get_descendants))
+            .route("/proof", get(get_proof))
             .route("/rows", get(get_page))
             .route("/org", get(get_by_organisation))
+            .route("/search", get(search))
             .route("/network", get(get_network))
+            .route("/status", get(get_status))
+            .route("/verify", get(verify_chain))
+            .route("/forks", get(get_forks))
+            .route("/rollbacks", get(get_rollbacks))
+            .route("/stakes", get(get_stakes))
+            .route("/committees", get(get_committees))
+            .route("/committee", get(get_committees_by_epoch))
             .with_state(state)
             .layer(CORS::new())
     }
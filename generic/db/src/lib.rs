@@ -1,16 +1,23 @@
+use std::collections::VecDeque;
 use tokio::{io, task};
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use tokio::task::JoinHandle;
 use administrator::web_server;
 use common::config::db_config::DbConfig;
+use common::config::genesis_config::GenesisConfig;
 use administrator::synchronizer::Synchronizer;
 use common::config::webserver_config::WebServerConfig;
-use common::memory::last_hash::LastHash;
+use common::memory::bootstrap::BOOTSTRAP;
+use common::memory::last_hash::{set_genesis, LastHash};
+use common::scheduler::Tick;
 use crate::administrator::{db_connector, notifier};
 use crate::repo::alter_repo::AlterRepo;
 use crate::service::alter_service::AlterService;
+use crate::service::pending_service::PendingService;
 use crate::repo::read_repo::ReadRepo;
 use crate::service::read_service::ReadService;
+use crate::service::verify_service::VerifyService;
+use crate::service::snapshot_service::SnapshotService;
 
 mod utils;
 mod administrator;
@@ -19,15 +26,26 @@ mod http;
 mod repo;
 mod service;
 
-pub async  fn power_module_db(webserver_config: WebServerConfig, db_config: DbConfig) -> io::Result<Vec<JoinHandle<()>>> {
+pub async  fn power_module_db(webserver_config: WebServerConfig, db_config: DbConfig, genesis_config: GenesisConfig, ticks: broadcast::Receiver<Tick>) -> io::Result<Vec<JoinHandle<()>>> {
     let db_pool = db_connector::create_pool(&db_config);
     
+    let alter_repo = AlterRepo { db_pool: db_pool.clone() };
+    let mut alter_service = AlterService { repo: alter_repo, last_hash: LastHash::new(), parked: VecDeque::new() };
+    alter_service.init_genesis(&genesis_config).await?;
+    alter_service.restore_tip().await;
+    if db_config.bootstrap_sync && alter_service.last_hash.get_height() == 0 {
+        let mut bootstrap = BOOTSTRAP.lock().await;
+        bootstrap.begin();
+        drop(bootstrap);
+    }
+
     let axum_pool = db_pool.clone();
     let axum_task = task::spawn(async move {
-        let read_repo = ReadRepo { db_pool: axum_pool };
+        let read_repo = ReadRepo { db_pool: axum_pool.clone() };
         let read_service = ReadService { repo: read_repo };
+        let verify_service = VerifyService { repo: ReadRepo { db_pool: axum_pool } };
 
-        web_server::start_server(webserver_config, read_service).await;
+        web_server::start_server(webserver_config, read_service, verify_service).await;
     });
 
     let (feedback_tx, feedback_rx) = mpsc::unbounded_channel::<Vec<(String, String)>>();
@@ -36,14 +54,73 @@ pub async  fn power_module_db(webserver_config: WebServerConfig, db_config: DbCo
         notifier::start_notifier(feedback_rx).await;
     });
     
-    let sync_pool = db_pool.clone();
-    let synchronizer_task = task::spawn(async move {
-        let alter_repo = AlterRepo { db_pool: sync_pool };
-        let alter_service = AlterService { repo: alter_repo, last_hash: LastHash::new() };
+    let rejection_task = task::spawn(async move {
+        notifier::start_rejection_notifier().await;
+    });
+
+    let pending_service = PendingService { repo: AlterRepo { db_pool: db_pool.clone() } };
+    pending_service.replay(&feedback_tx).await;
 
-        let mut synchronizer = Synchronizer {alter_service, feedback_sender: feedback_tx};
-        synchronizer.power_synchronizer().await;
+    let journal_task = task::spawn(async move {
+        let mut pending_service = pending_service;
+        pending_service.power_journal().await;
     });
 
-    Ok(vec![axum_task, notifier_task, synchronizer_task])
+    let synchronizer_task = task::spawn(async move {
+        let mut synchronizer = Synchronizer {
+            alter_service,
+            feedback_sender: feedback_tx,
+            resolving: None,
+            branch: None,
+            bootstrapping: None,
+            unconfirmed: None,
+            bootstrap_since: None,
+            bootstrap_ranges: 0,
+        };
+        synchronizer.power_synchronizer(ticks).await;
+    });
+
+    Ok(vec![axum_task, notifier_task, rejection_task, journal_task, synchronizer_task])
+}
+
+pub async fn verify_chain(db_config: &DbConfig, from: Option<String>) -> io::Result<(bool, String)> {
+    let read_repo = ReadRepo { db_pool: db_connector::create_pool(db_config) };
+    let genesis = read_repo
+        .get_genesis()
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::ConnectionRefused, format!("{:?}", e)))?;
+    if let Some(row) = genesis {
+        set_genesis(row.get("hash"));
+    }
+
+    let verify_service = VerifyService { repo: read_repo };
+    let report = verify_service
+        .verify(from)
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::ConnectionRefused, format!("{:?}", e)))?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Start hash is not in the chain"))?;
+
+    Ok((report.valid, serde_json::to_string_pretty(&report)?))
+}
+
+pub async fn export_snapshot(db_config: &DbConfig, path: &str, with_history: bool) -> io::Result<String> {
+    let db_pool = db_connector::create_pool(db_config);
+    let snapshot_service = SnapshotService {
+        read_repo: ReadRepo { db_pool: db_pool.clone() },
+        alter_repo: AlterRepo { db_pool },
+    };
+
+    let manifest = snapshot_service.export(path, with_history).await?;
+    Ok(serde_json::to_string_pretty(&manifest)?)
 }
+
+pub async fn import_snapshot(db_config: &DbConfig, path: &str) -> io::Result<String> {
+    let db_pool = db_connector::create_pool(db_config);
+    let mut snapshot_service = SnapshotService {
+        read_repo: ReadRepo { db_pool: db_pool.clone() },
+        alter_repo: AlterRepo { db_pool },
+    };
+
+    let manifest = snapshot_service.import(path).await?;
+    Ok(serde_json::to_string_pretty(&manifest)?)
+}
\ No newline at end of file
@@ -1,6 +1,25 @@
 use deadpool_postgres::{Pool, PoolError};
+use std::collections::HashSet;
+use chrono::{DateTime, Utc};
+use tokio_postgres::Row;
+use crate::dto::stake_dto::{CommitteeDTO, StakeDTO};
+use common::memory::last_hash::{genesis_hash, GENESIS_HASH};
+use common::certificate::QuorumCertificate;
+use common::memory::pending_log::PendingEvent;
+use deadpool_postgres::Transaction;
 use crate::dto::block_dto::BlockDTO;
 use crate::dto::connection_dto::ConnectionDTO;
+use crate::dto::fork_dto::ForkDTO;
+
+const BRANCH_QUERY: &str = "WITH RECURSIVE branch AS (
+        SELECT hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash, 1::BIGINT AS depth
+        FROM blocks WHERE previous_hash = $1
+        UNION ALL
+        SELECT b.hash, b.previous_hash, b.organization, b.payload, b.created_at, b.epoch, b.slot, b.merkle_root, b.leaf_index, b.height, b.proposer, b.proposer_signature, b.committee, b.simple_hash, branch.depth + 1
+        FROM blocks b JOIN branch ON b.previous_hash = branch.hash
+        WHERE branch.depth < $2
+    )
+    SELECT hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash FROM branch ORDER BY depth";
 
 pub struct AlterRepo {
     pub db_pool: Pool,
@@ -10,22 +29,23 @@ impl AlterRepo {
     pub async fn insert_many(
         &mut self,
         blocks: &[BlockDTO],
-    ) -> Result<Vec<String>, PoolError> {
+        committed: &[(String, String)],
+        certificates: &[QuorumCertificate],
+    ) -> Result<u64, PoolError> {
         let mut client =  self.db_pool.get().await?;
         let tx = client.transaction().await?;
 
         let stmt = tx.prepare(
             "INSERT INTO blocks
-         (hash, previous_hash, organization, payload, created_at)
-         VALUES ($1, $2, $3, $4, $5)
-         RETURNING hash"
+         (hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash, payload_json)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, try_parse_jsonb($4))
+         ON CONFLICT (hash) DO NOTHING"
         ).await?;
 
-        let mut inserted_hashes: Vec<String> = Vec::with_capacity(blocks.len());
-
+        let mut skipped = 0;
         for block in blocks {
-            let row = tx
-                .query_one(
+            let inserted = tx
+                .execute(
                     &stmt,
                     &[
                         &block.hash,
@@ -33,15 +53,37 @@ impl AlterRepo {
                         &block.organization,
                         &block.payload,
                         &block.created_at,
+                        &block.epoch,
+                        &block.slot,
+                        &block.merkle_root,
+                        &block.leaf_index,
+                        &block.height,
+                        &block.proposer,
+                        &block.proposer_signature,
+                        &block.committee,
+                        &block.simple_hash,
                     ],
                 )
                 .await?;
-            let returned_hash: String = row.get(0);
-            inserted_hashes.push(returned_hash);
+            if inserted == 0 {
+                skipped += 1;
+            }
+        }
+
+        let update_stmt = tx.prepare(
+            "UPDATE pending_blocks
+         SET full_hash = $2
+         WHERE hash = $1"
+        ).await?;
+
+        for (hash, full_hash) in committed {
+            tx.execute(&update_stmt, &[hash, full_hash]).await?;
         }
+
+        insert_certificates(&tx, certificates).await?;
         
         tx.commit().await?;
-        Ok(inserted_hashes)
+        Ok(skipped)
     }
     
     pub async fn update_connections(&mut self, updates: &[ConnectionDTO]) -> Result<(), PoolError> {
@@ -79,4 +121,381 @@ impl AlterRepo {
         tx.commit().await?;
         Ok(())
     }
+
+    pub async fn insert_forks(&mut self, forks: &[ForkDTO]) -> Result<(), PoolError> {
+        let mut client =  self.db_pool.get().await?;
+        let tx = client.transaction().await?;
+
+        let stmt = tx.prepare(
+            "INSERT INTO forks
+         (epoch, peer, local_hash, local_height, peer_hash, peer_height, local_only, peer_only, detected_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
+        ).await?;
+
+        for fork in forks {
+            tx.execute(
+                &stmt,
+                &[
+                    &fork.epoch,
+                    &fork.peer,
+                    &fork.local_hash,
+                    &fork.local_height,
+                    &fork.peer_hash,
+                    &fork.peer_height,
+                    &fork.local_only,
+                    &fork.peer_only,
+                    &fork.detected_at,
+                ],
+            ).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn count_certified_epochs(&self) -> Result<i64, PoolError> {
+        let client = self.db_pool.get().await?;
+        let row = client
+            .query_one(
+                "SELECT COUNT(*) FROM certificates c WHERE EXISTS (SELECT 1 FROM blocks b WHERE b.epoch = c.epoch)",
+                &[],
+            )
+            .await?;
+        Ok(row.get(0))
+    }
+
+    pub async fn certified_since(&self, ancestor: &str) -> Result<(i64, i64), PoolError> {
+        let client = self.db_pool.get().await?;
+        let row = client
+            .query_one(
+                "WITH ancestor AS (SELECT COALESCE((SELECT height FROM blocks WHERE hash = $1), 0) AS height)
+                SELECT ancestor.height, (
+                    SELECT COUNT(*) FROM certificates c WHERE c.epoch IN (
+                        SELECT epoch FROM blocks WHERE height > ancestor.height
+                    )
+                ) FROM ancestor",
+                &[&ancestor],
+            )
+            .await?;
+        Ok((row.get(0), row.get(1)))
+    }
+
+    pub async fn get_certificates(&self, epochs: &[i64]) -> Result<Vec<QuorumCertificate>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let rows = client
+            .query(
+                "SELECT certificate::text FROM certificates WHERE epoch = ANY($1) ORDER BY epoch",
+                &[&epochs],
+            )
+            .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| serde_json::from_str(row.get::<_, &str>(0)).ok())
+            .collect())
+    }
+
+    pub async fn get_branch(
+        &self,
+        locator: &[String],
+        limit: i64,
+    ) -> Result<(String, Vec<Row>, bool), PoolError> {
+        let client = self.db_pool.get().await?;
+
+        let found: HashSet<String> = client
+            .query("SELECT hash FROM blocks WHERE hash = ANY($1)", &[&locator])
+            .await?
+            .into_iter()
+            .map(|row| row.get(0))
+            .collect();
+        let genesis = genesis_hash();
+        let ancestor = locator
+            .iter()
+            .find(|hash| found.contains(*hash) || **hash == genesis)
+            .cloned()
+            .unwrap_or(genesis);
+
+        let mut rows = client
+            .query(BRANCH_QUERY, &[&ancestor, &(limit + 1)])
+            .await?;
+        let complete = rows.len() as i64 <= limit;
+        rows.truncate(limit as usize);
+
+        // Partial branches end on an epoch boundary so every epoch can be checked against its certificate.
+        if !complete {
+            let last_epoch: Option<i64> = rows.last().map(|row| row.get("epoch"));
+            if rows.iter().any(|row| Some(row.get::<_, i64>("epoch")) != last_epoch) {
+                while rows.last().map(|row| row.get::<_, i64>("epoch")) == last_epoch {
+                    rows.pop();
+                }
+            }
+        }
+
+        Ok((ancestor, rows, complete))
+    }
+
+    pub async fn rollback_and_apply(
+        &mut self,
+        ancestor: &str,
+        peer: &str,
+        blocks: &[BlockDTO],
+        certificates: &[QuorumCertificate],
+    ) -> Result<Vec<BlockDTO>, PoolError> {
+        let mut client =  self.db_pool.get().await?;
+        let tx = client.transaction().await?;
+
+        let reverted: Vec<BlockDTO> = tx
+            .query(BRANCH_QUERY, &[&ancestor, &i64::MAX])
+            .await?
+            .into_iter()
+            .map(BlockDTO::from_row)
+            .collect();
+        let reverted_hashes: Vec<String> = reverted.iter().map(|block| block.hash.clone()).collect();
+        let applied_hashes: Vec<String> = blocks.iter().map(|block| block.hash.clone()).collect();
+        let reverted_blocks = serde_json::to_string(&reverted).unwrap_or_else(|_| "[]".to_string());
+
+        let ancestor_height: i64 = tx
+            .query_opt("SELECT height FROM blocks WHERE hash = $1", &[&ancestor])
+            .await?
+            .and_then(|row| row.get("height"))
+            .unwrap_or(0);
+
+        tx.execute("DELETE FROM blocks WHERE hash = ANY($1)", &[&reverted_hashes]).await?;
+
+        let reverted_epochs: Vec<i64> = reverted.iter().map(|block| block.epoch).collect();
+        tx.execute(
+            "DELETE FROM certificates
+         WHERE epoch = ANY($1)
+         AND NOT EXISTS (SELECT 1 FROM blocks WHERE blocks.epoch = certificates.epoch)",
+            &[&reverted_epochs],
+        ).await?;
+
+        let insert_stmt = tx.prepare(
+            "INSERT INTO blocks
+         (hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash, payload_json)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, try_parse_jsonb($4))"
+        ).await?;
+
+        for (index, block) in blocks.iter().enumerate() {
+            let height = ancestor_height + index as i64 + 1;
+            tx.execute(
+                &insert_stmt,
+                &[
+                    &block.hash,
+                    &block.previous_hash,
+                    &block.organization,
+                    &block.payload,
+                    &block.created_at,
+                    &block.epoch,
+                    &block.slot,
+                    &block.merkle_root,
+                    &block.leaf_index,
+                    &height,
+                    &block.proposer,
+                    &block.proposer_signature,
+                    &block.committee,
+                    &block.simple_hash,
+                ],
+            ).await?;
+        }
+
+        tx.execute(
+            "INSERT INTO rollbacks
+         (ancestor_hash, peer, reverted_hashes, applied_hashes, reverted_blocks, reverted_at)
+         VALUES ($1, $2, $3, $4, $5::text::jsonb, $6)",
+            &[
+                &ancestor,
+                &peer,
+                &reverted_hashes,
+                &applied_hashes,
+                &reverted_blocks,
+                &Utc::now(),
+            ],
+        ).await?;
+
+        insert_certificates(&tx, certificates).await?;
+
+        tx.commit().await?;
+        Ok(reverted)
+    }
+
+    pub async fn save_stakes(&mut self, stakes: &[StakeDTO], seed: &str) -> Result<(), PoolError> {
+        let mut client =  self.db_pool.get().await?;
+        let tx = client.transaction().await?;
+
+        let upsert_stmt = tx.prepare(
+            "INSERT INTO stakes
+         (url, stake, reward, penalty, public_key, updated_at, slashed)
+         VALUES ($1, $2, $3, $4, $5, $6, $7::text::jsonb)
+         ON CONFLICT (url) DO UPDATE SET
+         stake = EXCLUDED.stake, reward = EXCLUDED.reward, penalty = EXCLUDED.penalty,
+         public_key = EXCLUDED.public_key, updated_at = EXCLUDED.updated_at, slashed = EXCLUDED.slashed"
+        ).await?;
+
+        for stake in stakes {
+            tx.execute(
+                &upsert_stmt,
+                &[
+                    &stake.url,
+                    &stake.stake,
+                    &stake.reward,
+                    &stake.penalty,
+                    &stake.public_key,
+                    &stake.updated_at,
+                    &stake.slashed,
+                ],
+            ).await?;
+        }
+
+        tx.execute(
+            "INSERT INTO network_seed (id, seed, updated_at) VALUES (1, $1, $2)
+         ON CONFLICT (id) DO UPDATE SET seed = EXCLUDED.seed, updated_at = EXCLUDED.updated_at",
+            &[&seed, &Utc::now()],
+        ).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn insert_committees(&mut self, committees: &[CommitteeDTO]) -> Result<(), PoolError> {
+        let mut client =  self.db_pool.get().await?;
+        let tx = client.transaction().await?;
+
+        let insert_stmt = tx.prepare(
+            "INSERT INTO committees
+         (epoch, slot, members, seed, recorded_at)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (epoch, slot) DO UPDATE SET members = EXCLUDED.members, recorded_at = EXCLUDED.recorded_at"
+        ).await?;
+
+        for committee in committees {
+            tx.execute(
+                &insert_stmt,
+                &[
+                    &committee.epoch,
+                    &committee.slot,
+                    &committee.members,
+                    &committee.seed,
+                    &committee.recorded_at,
+                ],
+            ).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn load_stakes(&self) -> Result<(Vec<Row>, Option<String>), PoolError> {
+        let client = self.db_pool.get().await?;
+        let rows = client
+            .query("SELECT url, stake, reward, penalty, public_key, slashed::text AS slashed, updated_at FROM stakes", &[])
+            .await?;
+        let seed = client
+            .query_opt("SELECT seed FROM network_seed WHERE id = 1", &[])
+            .await?
+            .map(|row| row.get("seed"));
+        Ok((rows, seed))
+    }
+
+    pub async fn apply_pending_events(&mut self, events: &[PendingEvent]) -> Result<(), PoolError> {
+        let mut client =  self.db_pool.get().await?;
+        let tx = client.transaction().await?;
+
+        let insert_stmt = tx.prepare(
+            "INSERT INTO pending_blocks
+         (hash, organization, payload, feedback, accepted_at)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (hash) DO UPDATE SET feedback = COALESCE(pending_blocks.feedback, EXCLUDED.feedback)"
+        ).await?;
+
+        let remove_stmt = tx.prepare(
+            "DELETE FROM pending_blocks
+         WHERE hash = $1"
+        ).await?;
+
+        let accepted_at = Utc::now();
+        for event in events {
+            match event {
+                PendingEvent::Accepted { hash, organization, payload, feedback } => {
+                    tx.execute(
+                        &insert_stmt,
+                        &[hash, organization, payload, feedback, &accepted_at],
+                    ).await?;
+                }
+                PendingEvent::Delivered { hash } => {
+                    tx.execute(&remove_stmt, &[hash]).await?;
+                }
+            }
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_pending_blocks(&self) -> Result<Vec<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let rows = client
+            .query("SELECT hash, organization, payload, feedback, full_hash, accepted_at FROM pending_blocks ORDER BY accepted_at", &[])
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn ensure_genesis(
+        &mut self,
+        chain_id: &str,
+        derived_hash: &str,
+        validators: &[String],
+        created_at: &DateTime<Utc>,
+    ) -> Result<Row, PoolError> {
+        let mut client =  self.db_pool.get().await?;
+        let tx = client.transaction().await?;
+
+        let stored = tx
+            .query_opt("SELECT chain_id, hash, validators, created_at FROM genesis WHERE id = 1", &[])
+            .await?;
+        if let Some(row) = stored {
+            tx.commit().await?;
+            return Ok(row);
+        }
+
+        let legacy_chain = tx
+            .query_opt("SELECT hash FROM blocks WHERE previous_hash = $1 LIMIT 1", &[&GENESIS_HASH])
+            .await?
+            .is_some();
+        let hash = if legacy_chain { GENESIS_HASH } else { derived_hash };
+
+        let row = tx
+            .query_one(
+                "INSERT INTO genesis
+         (id, chain_id, hash, validators, created_at)
+         VALUES (1, $1, $2, $3, $4)
+         RETURNING chain_id, hash, validators, created_at",
+                &[&chain_id, &hash, &validators, created_at],
+            )
+            .await?;
+
+        tx.commit().await?;
+        Ok(row)
+    }
+
+    pub async fn get_tip(&self) -> Result<Option<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let row = client
+            .query_opt("SELECT hash, height FROM blocks WHERE height IS NOT NULL ORDER BY height DESC, created_at DESC LIMIT 1", &[])
+            .await?;
+        Ok(row)
+    }
 }
+
+async fn insert_certificates(tx: &Transaction<'_>, certificates: &[QuorumCertificate]) -> Result<(), PoolError> {
+    let stmt = tx.prepare(
+        "INSERT INTO certificates
+         (epoch, digest, certificate)
+         VALUES ($1, $2, $3::text::jsonb)
+         ON CONFLICT (epoch) DO UPDATE SET digest = EXCLUDED.digest, certificate = EXCLUDED.certificate"
+    ).await?;
+
+    for certificate in certificates {
+        let encoded = serde_json::to_string(certificate).unwrap_or_else(|_| "{}".to_string());
+        tx.execute(&stmt, &[&(certificate.epoch as i64), &certificate.digest, &encoded]).await?;
+    }
+    Ok(())
+}
\ No newline at end of file
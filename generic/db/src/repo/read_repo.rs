@@ -1,5 +1,7 @@
 use deadpool_postgres::{Pool, PoolError};
 use tokio_postgres::Row;
+use crate::utils::cursor::{Cursor, KeyCursor, PairCursor, SequenceCursor};
+use crate::dto::search_dto::{SearchFilter, SortOrder};
 
 pub struct ReadRepo {
     pub db_pool: Pool,
@@ -7,20 +9,64 @@ pub struct ReadRepo {
 
 impl ReadRepo {
 
-    pub async fn get_page(&self, offset: i64) -> Result<Vec<Row>, PoolError> {
+    pub async fn get_page(&self, cursor: Option<Cursor>, limit: i64) -> Result<Vec<Row>, PoolError> {
         let client = self.db_pool.get().await?;
-        let stmt = "SELECT hash, previous_hash, organization, payload, created_at FROM blocks ORDER BY created_at DESC OFFSET $1 LIMIT 20";
+        let (created_at, hash) = cursor.unzip();
+        let stmt = "SELECT hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash FROM blocks WHERE ($1::timestamptz IS NULL OR (created_at, hash) < ($1, $2::text)) ORDER BY created_at DESC, hash DESC LIMIT $3";
         let rows = client
-            .query(stmt, &[&offset])
+            .query(stmt, &[&created_at, &hash, &limit])
             .await?;
         Ok(rows)
     }
     
-    pub async fn get_by_organization(&self, organization: String, offset: i64) -> Result<Vec<Row>, PoolError> {
+    pub async fn get_by_organization(&self, organization: String, cursor: Option<Cursor>, limit: i64) -> Result<Vec<Row>, PoolError> {
         let client = self.db_pool.get().await?;
-        let stmt = "SELECT hash, previous_hash, organization, payload, created_at FROM blocks WHERE organization = $1 ORDER BY created_at DESC OFFSET $2 LIMIT 20";
+        let (created_at, hash) = cursor.unzip();
+        let stmt = "SELECT hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash FROM blocks WHERE organization = $1 AND ($2::timestamptz IS NULL OR (created_at, hash) < ($2, $3::text)) ORDER BY created_at DESC, hash DESC LIMIT $4";
         let rows = client
-            .query(stmt, &[&organization, &offset])
+            .query(stmt, &[&organization, &created_at, &hash, &limit])
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn search(&self, filter: &SearchFilter, cursor: Option<Cursor>, limit: i64) -> Result<Vec<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let (created_at, hash) = cursor.unzip();
+        let (comparison, direction) = match filter.order {
+            SortOrder::Asc => (">", "ASC"),
+            SortOrder::Desc => ("<", "DESC"),
+        };
+        let stmt = format!(
+            "SELECT hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash FROM blocks \
+            WHERE ($1::text IS NULL OR organization = $1) \
+            AND ($2::text IS NULL OR proposer = $2) \
+            AND ($3::timestamptz IS NULL OR created_at >= $3) \
+            AND ($4::timestamptz IS NULL OR created_at <= $4) \
+            AND ($5::bigint IS NULL OR height >= $5) \
+            AND ($6::bigint IS NULL OR height <= $6) \
+            AND ($7::text IS NULL OR payload_json @> $7::text::jsonb) \
+            AND ($8::text IS NULL OR payload_json @@ $8::text::jsonpath) \
+            AND ($9::timestamptz IS NULL OR (created_at, hash) {} ($9, $10::text)) \
+            ORDER BY created_at {}, hash {} LIMIT $11",
+            comparison, direction, direction
+        );
+        let rows = client
+            .query(
+                &stmt,
+                &[
+                    &filter.organization,
+                    &filter.proposer,
+                    &filter.from,
+                    &filter.to,
+                    &filter.min_height,
+                    &filter.max_height,
+                    &filter.contains,
+                    &filter.path,
+                    &created_at,
+                    &hash,
+                    &limit,
+                ],
+            )
             .await?;
         Ok(rows)
     }
@@ -35,13 +81,168 @@ impl ReadRepo {
         Ok(rows)
     }
 
+    pub async fn get_forks(&self, cursor: Option<SequenceCursor>, limit: i64) -> Result<Vec<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let (detected_at, id) = cursor.unzip();
+        let stmt = "SELECT id, epoch, peer, local_hash, local_height, peer_hash, peer_height, local_only, peer_only, detected_at FROM forks WHERE ($1::timestamptz IS NULL OR (detected_at, id) < ($1, $2::bigint)) ORDER BY detected_at DESC, id DESC LIMIT $3";
+        let rows = client
+            .query(stmt, &[&detected_at, &id, &limit])
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_rollbacks(&self, cursor: Option<SequenceCursor>, limit: i64) -> Result<Vec<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let (reverted_at, id) = cursor.unzip();
+        let stmt = "SELECT id, ancestor_hash, peer, reverted_hashes, applied_hashes, reverted_blocks::text AS reverted_blocks, reverted_at FROM rollbacks WHERE ($1::timestamptz IS NULL OR (reverted_at, id) < ($1, $2::bigint)) ORDER BY reverted_at DESC, id DESC LIMIT $3";
+        let rows = client
+            .query(stmt, &[&reverted_at, &id, &limit])
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_stakes(&self, cursor: Option<KeyCursor>, limit: i64) -> Result<Vec<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let (stake, url) = cursor.unzip();
+        let stmt = "SELECT url, stake, reward, penalty, public_key, slashed::text AS slashed, updated_at FROM stakes WHERE ($1::bigint IS NULL OR stake < $1 OR (stake = $1 AND url > $2::text)) ORDER BY stake DESC, url LIMIT $3";
+        let rows = client
+            .query(stmt, &[&stake, &url, &limit])
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_committees(&self, cursor: Option<PairCursor>, limit: i64) -> Result<Vec<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let (epoch, slot) = cursor.unzip();
+        let stmt = "SELECT epoch, slot, members, seed, recorded_at FROM committees WHERE ($1::bigint IS NULL OR (epoch, slot) < ($1, $2::bigint)) ORDER BY epoch DESC, slot DESC LIMIT $3";
+        let rows = client
+            .query(stmt, &[&epoch, &slot, &limit])
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_committees_by_epoch(&self, epoch: i64) -> Result<Vec<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let stmt = "SELECT epoch, slot, members, seed, recorded_at FROM committees WHERE epoch = $1 ORDER BY slot";
+        let rows = client
+            .query(stmt, &[&epoch])
+            .await?;
+        Ok(rows)
+    }
+
     pub async fn get_by_hash(&self, hash: String) -> Result<Option<Row>, PoolError> {
         let client = self.db_pool.get().await?;
-        let stmt = "SELECT hash, previous_hash, organization, payload, created_at FROM blocks WHERE hash = $1";
+        let stmt = "SELECT hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash FROM blocks WHERE hash = $1";
+        let row = client
+            .query_opt(stmt, &[&hash])
+            .await?;
+        Ok(row)
+    }
+
+    pub async fn get_by_height(&self, height: i64) -> Result<Option<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let stmt = "SELECT hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash FROM blocks WHERE height = $1 ORDER BY created_at LIMIT 1";
+        let row = client
+            .query_opt(stmt, &[&height])
+            .await?;
+        Ok(row)
+    }
+
+    pub async fn get_successor(&self, hash: String) -> Result<Option<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let stmt = "SELECT hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash FROM blocks WHERE previous_hash = $1 ORDER BY created_at, hash LIMIT 1";
+        let row = client
+            .query_opt(stmt, &[&hash])
+            .await?;
+        Ok(row)
+    }
+
+    pub async fn get_predecessor(&self, hash: String) -> Result<Option<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let stmt = "SELECT p.hash, p.previous_hash, p.organization, p.payload, p.created_at, p.epoch, p.slot, p.merkle_root, p.leaf_index, p.height, p.proposer, p.proposer_signature, p.committee, p.simple_hash FROM blocks b JOIN blocks p ON p.hash = b.previous_hash WHERE b.hash = $1";
         let row = client
             .query_opt(stmt, &[&hash])
             .await?;
         Ok(row)
     }
+
+    pub async fn get_ancestors(&self, hash: String, depth: i64) -> Result<Vec<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let stmt = "WITH RECURSIVE ancestors AS (
+                SELECT p.hash, p.previous_hash, p.organization, p.payload, p.created_at, p.epoch, p.slot, p.merkle_root, p.leaf_index, p.height, p.proposer, p.proposer_signature, p.committee, p.simple_hash, 1::BIGINT AS depth
+                FROM blocks b JOIN blocks p ON p.hash = b.previous_hash WHERE b.hash = $1
+                UNION ALL
+                SELECT p.hash, p.previous_hash, p.organization, p.payload, p.created_at, p.epoch, p.slot, p.merkle_root, p.leaf_index, p.height, p.proposer, p.proposer_signature, p.committee, p.simple_hash, ancestors.depth + 1
+                FROM blocks p JOIN ancestors ON p.hash = ancestors.previous_hash
+                WHERE ancestors.depth < $2
+            )
+            SELECT hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash FROM ancestors ORDER BY depth";
+        let rows = client
+            .query(stmt, &[&hash, &depth])
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_descendants(&self, hash: String, depth: i64) -> Result<Vec<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let stmt = "WITH RECURSIVE descendants AS (
+                SELECT hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash, 1::BIGINT AS depth
+                FROM blocks WHERE previous_hash = $1
+                UNION ALL
+                SELECT b.hash, b.previous_hash, b.organization, b.payload, b.created_at, b.epoch, b.slot, b.merkle_root, b.leaf_index, b.height, b.proposer, b.proposer_signature, b.committee, b.simple_hash, descendants.depth + 1
+                FROM blocks b JOIN descendants ON b.previous_hash = descendants.hash
+                WHERE descendants.depth < $2
+            )
+            SELECT hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash FROM descendants ORDER BY depth, created_at, hash";
+        let rows = client
+            .query(stmt, &[&hash, &depth])
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_genesis(&self) -> Result<Option<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let stmt = "SELECT chain_id, hash, validators, created_at FROM genesis WHERE id = 1";
+        let row = client
+            .query_opt(stmt, &[])
+            .await?;
+        Ok(row)
+    }
+
+    pub async fn get_chain_blocks(&self) -> Result<Vec<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let stmt = "SELECT hash, previous_hash, organization, payload, created_at, epoch, slot, merkle_root, leaf_index, height, proposer, proposer_signature, committee, simple_hash FROM blocks ORDER BY height NULLS LAST, created_at";
+        let rows = client
+            .query(stmt, &[])
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_all_committees(&self) -> Result<Vec<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let stmt = "SELECT epoch, slot, members, seed, recorded_at FROM committees ORDER BY epoch, slot";
+        let rows = client
+            .query(stmt, &[])
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_chain_links(&self) -> Result<Vec<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let stmt = "SELECT hash, previous_hash, simple_hash, payload, height FROM blocks";
+        let rows = client
+            .query(stmt, &[])
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_merkle_leaves(&self, merkle_root: String, epoch: i64, slot: i64) -> Result<Vec<Row>, PoolError> {
+        let client = self.db_pool.get().await?;
+        let stmt = "SELECT payload, leaf_index FROM blocks WHERE merkle_root = $1 AND epoch = $2 AND slot = $3 ORDER BY leaf_index";
+        let rows = client
+            .query(stmt, &[&merkle_root, &epoch, &slot])
+            .await?;
+        Ok(rows)
+    }
     
-}
+}
\ No newline at end of file
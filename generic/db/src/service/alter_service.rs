@@ -1,61 +1,481 @@
 use crate::{dto::block_dto::BlockDTO, repo::alter_repo::AlterRepo};
 use base64::{engine::general_purpose, Engine as _};
-use blake3;
-use chrono::{DateTime, Utc};
-use common::memory::last_hash::LastHash;
+use common::memory::last_hash::{genesis_hash, set_genesis, LastHash};
+use common::config::genesis_config::GenesisConfig;
+use std::io::{self, ErrorKind};
+use std::collections::{HashSet, VecDeque};
+use common::memory::db_queue::{EpochHeader, OrderedBlock};
 use crate::dto::connection_dto::ConnectionDTO;
+use crate::dto::fork_dto::ForkDTO;
+use chrono::Utc;
+use common::logger::Logger;
+use common::memory::blocks_queue::BLOCKS_QUEUE;
+use common::memory::feedback_queue::FEEDBACK_QUEUE;
+use common::memory::chain_tips::ChainTip;
+use common::certificate::{digest_of, QuorumCertificate};
+use common::memory::chain_sync::{ChainBlock, ChainRequest, ChainResponse};
+use common::round::Round;
+use common::memory::stake_table::{CommitteeRecord, StakeEntry, StakeSnapshot};
+use crate::dto::stake_dto::{CommitteeDTO, StakeDTO};
+use crate::utils::{chain_hash, merkle};
+
+const FEEDBACK_AFTER_ATTEMPTS: u32 = 5;
 
 pub struct AlterService {
     pub(crate) repo: AlterRepo,
     pub(crate) last_hash: LastHash,
+    pub(crate) parked: VecDeque<(Vec<OrderedBlock>, EpochHeader, u32)>,
 }
 
 impl AlterService {
     pub async fn create_blocks(
         &mut self,
-        raw_blocks: Vec<((String, String, String), DateTime<Utc>)>,
+        raw_blocks: Vec<OrderedBlock>,
+        header: EpochHeader,
     ) -> Vec<(String, String)> {
+        let (raw_blocks, undecodable): (Vec<OrderedBlock>, Vec<OrderedBlock>) = raw_blocks
+            .into_iter()
+            .partition(|((simple_hash_b64, _, _), _, _)| general_purpose::STANDARD.decode(simple_hash_b64).is_ok());
+        if !undecodable.is_empty() {
+            Logger::console("db", &format!("Skipped {} blocks with undecodable hashes", undecodable.len()));
+            report_failed(&undecodable, "Block hash is not valid base64", true).await;
+        }
+        if !raw_blocks.is_empty() {
+            self.parked.push_back((raw_blocks, header, 0));
+        }
+
+        let mut committed: Vec<(String, String)> = Vec::new();
+        while let Some((raw_blocks, header, attempts)) = self.parked.pop_front() {
+            match self.commit_batch(&raw_blocks, &header).await {
+                Ok(hashes) => committed.extend(hashes),
+                Err(e) => {
+                    let attempts = attempts + 1;
+                    Logger::console(
+                        "db",
+                        &format!("Parked batch of {} blocks after attempt {}: {:?}", raw_blocks.len(), attempts, e),
+                    );
+                    if attempts == FEEDBACK_AFTER_ATTEMPTS {
+                        let reason = format!("Commit failed after {} attempts, still retrying", attempts);
+                        report_failed(&raw_blocks, &reason, false).await;
+                    }
+                    self.parked.push_front((raw_blocks, header, attempts));
+                    break;
+                }
+            }
+        }
+
+        committed
+    }
+
+    async fn commit_batch(
+        &mut self,
+        raw_blocks: &[OrderedBlock],
+        header: &EpochHeader,
+    ) -> io::Result<Vec<(String, String)>> {
         let last_hash = self.last_hash.get();
+        let mut height = self.last_hash.get_height();
+        let (proposer, proposer_signature, committee, certificate) = header;
         
         let mut blocks_dto: Vec<BlockDTO> = Vec::new();
-        let mut prev_hash = general_purpose::URL_SAFE_NO_PAD.decode(last_hash).unwrap();
+        let mut prev_hash = general_purpose::URL_SAFE_NO_PAD
+            .decode(&last_hash)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, format!("Undecodable tip {}: {:?}", last_hash, e)))?;
 
         let mut inserted_hashes: Vec<(String, String)> = Vec::with_capacity(raw_blocks.len());
 
-        for ((simple_hash_b64, organization, payload), created_at) in raw_blocks {
+        for ((simple_hash_b64, organization, payload), created_at, round) in raw_blocks {
             let prev_hash_b64 = general_purpose::URL_SAFE_NO_PAD.encode(&prev_hash);
-            let simple_hash = general_purpose::STANDARD.decode(&*simple_hash_b64).unwrap();
+            let simple_hash = match general_purpose::STANDARD.decode(simple_hash_b64) {
+                Ok(simple_hash) => simple_hash,
+                Err(_) => continue,
+            };
 
-            let mut hasher = blake3::Hasher::new();
-            hasher.update(&prev_hash);
-            hasher.update(&simple_hash);
-            let mut out = [0u8; 64];
-            hasher.finalize_xof().fill(&mut out);
+            let out = chain_hash::link_hash(&prev_hash, &simple_hash);
 
             let hash = general_purpose::URL_SAFE_NO_PAD.encode(out);
+            height += 1;
 
             blocks_dto.push(BlockDTO {
                 hash: hash.clone(),
                 previous_hash: prev_hash_b64,
                 organization: organization.clone(),
                 payload: payload.clone(),
-                created_at: created_at.clone(),
+                created_at: *created_at,
+                epoch: round.epoch as i64,
+                slot: round.slot as i64,
+                merkle_root: None,
+                leaf_index: None,
+                height: Some(height),
+                proposer: Some(proposer.clone()),
+                proposer_signature: Some(proposer_signature.clone()),
+                committee: Some(committee.clone()),
+                simple_hash: Some(simple_hash_b64.clone()),
             });
             
-            inserted_hashes.push((simple_hash_b64, hash));
+            inserted_hashes.push((simple_hash_b64.clone(), hash));
 
             prev_hash = out.to_vec();
         }
+        assign_merkle_roots(&mut blocks_dto);
         
-        let _ = self.repo.insert_many(&blocks_dto).await;
+        let certificates: Vec<QuorumCertificate> = certificate.iter().cloned().collect();
+        let skipped = self
+            .repo
+            .insert_many(&blocks_dto, &inserted_hashes, &certificates)
+            .await
+            .map_err(|e| io::Error::other(format!("{:?}", e)))?;
+        if skipped > 0 {
+            Logger::console("db", &format!("Skipped {} blocks that were already committed", skipped));
+        }
         
         let new_last_hash = general_purpose::URL_SAFE_NO_PAD.encode(prev_hash);
-
-        self.last_hash.set(new_last_hash);
+        self.last_hash.set(new_last_hash, height);
         
-        inserted_hashes
+        Ok(inserted_hashes)
     }
     
+    pub async fn current_tip(&self, epoch: u64, blocks: Vec<String>) -> ChainTip {
+        let certified_epochs = match self.repo.count_certified_epochs().await {
+            Ok(count) => count,
+            Err(e) => {
+                Logger::console("fork", &format!("Failed to count certified epochs: {:?}", e));
+                0
+            }
+        };
+
+        ChainTip {
+            epoch,
+            hash: self.last_hash.get(),
+            height: self.last_hash.get_height(),
+            blocks,
+            certified_epochs,
+        }
+    }
+
+    pub async fn get_branch(&self, request: &ChainRequest) -> Option<ChainResponse> {
+        let (ancestor, rows, complete) = match self.repo.get_branch(&request.locator, request.limit).await {
+            Ok(branch) => branch,
+            Err(e) => {
+                Logger::console("fork", &format!("Failed to read branch for {}: {:?}", request.peer, e));
+                return None;
+            }
+        };
+        let blocks: Vec<ChainBlock> = rows
+            .into_iter()
+            .map(|row| BlockDTO::from_row(row).to_chain_block())
+            .collect();
+
+        let mut epochs: Vec<i64> = blocks.iter().map(|block| block.epoch).collect();
+        epochs.dedup();
+        let certificates = match self.repo.get_certificates(&epochs).await {
+            Ok(certificates) => certificates,
+            Err(e) => {
+                Logger::console("fork", &format!("Failed to read certificates for {}: {:?}", request.peer, e));
+                Vec::new()
+            }
+        };
+
+        Some(ChainResponse {
+            peer: request.peer.clone(),
+            ancestor,
+            blocks,
+            complete,
+            certificates,
+        })
+    }
+
+    pub async fn switch_branch(&mut self, response: ChainResponse) -> bool {
+        let mut previous_hash = response.ancestor.clone();
+        for block in &response.blocks {
+            let recomputed = match &block.simple_hash {
+                Some(simple_hash) => chain_hash::verify_block(&block.previous_hash, &block.hash, simple_hash, &block.payload),
+                None => false,
+            };
+            if block.previous_hash != previous_hash || !recomputed {
+                Logger::console(
+                    "fork",
+                    &format!("Branch from {} is not linked at {}", response.peer, block.hash),
+                );
+                return false;
+            }
+            previous_hash = block.hash.clone();
+        }
+
+        let certificates = certified(&response.blocks, &response.certificates);
+        let (ancestor_height, local_certified) = match self.repo.certified_since(&response.ancestor).await {
+            Ok(certified) => certified,
+            Err(e) => {
+                Logger::console("fork", &format!("Failed to count local certificates: {:?}", e));
+                return false;
+            }
+        };
+        let local = ChainTip {
+            epoch: 0,
+            hash: self.last_hash.get(),
+            height: self.last_hash.get_height(),
+            blocks: Vec::new(),
+            certified_epochs: local_certified,
+        };
+        let remote = ChainTip {
+            epoch: 0,
+            hash: previous_hash.clone(),
+            height: ancestor_height + response.blocks.len() as i64,
+            blocks: Vec::new(),
+            certified_epochs: certificates.len() as i64,
+        };
+        if !remote.outranks(&local) {
+            Logger::console(
+                "fork",
+                &format!(
+                    "Branch from {} carries {} verified certificates against {} local, keeping local chain",
+                    response.peer, remote.certified_epochs, local.certified_epochs
+                ),
+            );
+            return false;
+        }
+
+        let mut blocks: Vec<BlockDTO> = response
+            .blocks
+            .into_iter()
+            .map(BlockDTO::from_chain_block)
+            .collect();
+        assign_merkle_roots(&mut blocks);
+
+        match self.repo.rollback_and_apply(&response.ancestor, &response.peer, &blocks, &certificates).await {
+            Ok(reverted) => {
+                let new_height = ancestor_height + blocks.len() as i64;
+
+                let applied: HashSet<&String> = blocks.iter().filter_map(|block| block.simple_hash.as_ref()).collect();
+                let mut blocks_queue = BLOCKS_QUEUE.lock().await;
+                let requeued = reverted
+                    .iter()
+                    .filter(|block| block.simple_hash.as_ref().map(|hash| !applied.contains(hash)).unwrap_or(false))
+                    .filter(|block| {
+                        blocks_queue.reopen(
+                            block.simple_hash.clone().unwrap_or_default(),
+                            block.organization.clone(),
+                            block.payload.clone(),
+                        )
+                    })
+                    .count();
+                drop(blocks_queue);
+                self.last_hash.set(previous_hash.clone(), new_height);
+
+                Logger::console(
+                    "fork",
+                    &format!(
+                        "Switched to branch of {} at ancestor={} reverted={} applied={} requeued={} tip={}@{}",
+                        response.peer, response.ancestor, reverted.len(), blocks.len(), requeued, previous_hash, new_height
+                    ),
+                );
+                true
+            }
+            Err(e) => {
+                Logger::console("fork", &format!("Failed to switch branch: {:?}", e));
+                false
+            }
+        }
+    }
+
+    pub async fn append_range(&mut self, response: ChainResponse) -> Option<Vec<(String, String)>> {
+        let mut previous_hash = self.last_hash.get();
+        if response.ancestor != previous_hash {
+            Logger::console(
+                "bootstrap",
+                &format!("Range from {} starts at {} instead of {}", response.peer, response.ancestor, previous_hash),
+            );
+            return None;
+        }
+
+        for block in &response.blocks {
+            let recomputed = match &block.simple_hash {
+                Some(simple_hash) => chain_hash::verify_block(&block.previous_hash, &block.hash, simple_hash, &block.payload),
+                None => false,
+            };
+            if block.previous_hash != previous_hash || !recomputed {
+                Logger::console(
+                    "bootstrap",
+                    &format!("Range from {} breaks at {}", response.peer, block.hash),
+                );
+                return None;
+            }
+            previous_hash = block.hash.clone();
+        }
+
+        let certificates = certified(&response.blocks, &response.certificates);
+        let mut height = self.last_hash.get_height();
+        let mut blocks: Vec<BlockDTO> = response
+            .blocks
+            .into_iter()
+            .map(|block| {
+                height += 1;
+                let mut block = BlockDTO::from_chain_block(block);
+                block.height = Some(height);
+                block
+            })
+            .collect();
+        assign_merkle_roots(&mut blocks);
+        let committed: Vec<(String, String)> = blocks
+            .iter()
+            .filter_map(|block| block.simple_hash.clone().map(|simple_hash| (simple_hash, block.hash.clone())))
+            .collect();
+
+        match self.repo.insert_many(&blocks, &committed, &certificates).await {
+            Ok(_) => {
+                self.last_hash.set(previous_hash, height);
+                Some(committed)
+            }
+            Err(e) => {
+                Logger::console("bootstrap", &format!("Failed to load range: {:?}", e));
+                None
+            }
+        }
+    }
+
+    pub async fn record_forks(&mut self, raw_forks: &[(String, ChainTip, ChainTip)]) {
+        let mut forks: Vec<ForkDTO> = Vec::with_capacity(raw_forks.len());
+
+        for (peer, local, remote) in raw_forks.iter().cloned() {
+            let local_only: Vec<String> = local
+                .blocks
+                .iter()
+                .filter(|hash| !remote.blocks.contains(hash))
+                .cloned()
+                .collect();
+            let peer_only: Vec<String> = remote
+                .blocks
+                .iter()
+                .filter(|hash| !local.blocks.contains(hash))
+                .cloned()
+                .collect();
+            let first_difference = local
+                .blocks
+                .iter()
+                .zip(remote.blocks.iter())
+                .position(|(local_hash, remote_hash)| local_hash != remote_hash)
+                .unwrap_or(local.blocks.len().min(remote.blocks.len()));
+
+            Logger::console(
+                "fork",
+                &format!(
+                    "epoch={} peer={} local={}@{} remote={}@{} first_difference={} local_only={:?} peer_only={:?}",
+                    local.epoch, peer, local.hash, local.height, remote.hash, remote.height,
+                    first_difference, local_only, peer_only
+                ),
+            );
+
+            forks.push(ForkDTO {
+                epoch: local.epoch as i64,
+                peer,
+                local_hash: local.hash,
+                local_height: local.height,
+                peer_hash: remote.hash,
+                peer_height: remote.height,
+                local_only,
+                peer_only,
+                detected_at: Utc::now(),
+            });
+        }
+
+        if forks.is_empty() {
+            return;
+        }
+        if let Err(e) = self.repo.insert_forks(&forks).await {
+            Logger::console("fork", &format!("Failed to record forks: {:?}", e));
+        }
+    }
+
+    pub async fn persist_stakes(&mut self, snapshot: Option<StakeSnapshot>, committees: Vec<CommitteeRecord>) {
+        if let Some(snapshot) = snapshot {
+            let updated_at = Utc::now();
+            let stakes: Vec<StakeDTO> = snapshot.nodes.into_iter().map(|entry| StakeDTO {
+                url: entry.url,
+                stake: entry.stake,
+                reward: entry.reward,
+                penalty: entry.penalty,
+                public_key: entry.public_key,
+                slashed: serde_json::to_string(&entry.slashed).unwrap_or_else(|_| "[]".to_string()),
+                updated_at,
+            }).collect();
+            if let Err(e) = self.repo.save_stakes(&stakes, &snapshot.seed).await {
+                Logger::console("stake", &format!("Failed to save stakes: {:?}", e));
+            }
+        }
+
+        if committees.is_empty() {
+            return;
+        }
+        let committees: Vec<CommitteeDTO> = committees.into_iter().map(|record| CommitteeDTO {
+            epoch: record.round.epoch as i64,
+            slot: record.round.slot as i64,
+            members: record.members,
+            seed: record.seed,
+            recorded_at: Utc::now(),
+        }).collect();
+        if let Err(e) = self.repo.insert_committees(&committees).await {
+            Logger::console("stake", &format!("Failed to record committees: {:?}", e));
+        }
+    }
+
+    pub async fn init_genesis(&mut self, genesis: &GenesisConfig) -> io::Result<()> {
+        let derived_hash = chain_hash::genesis_block_hash(genesis);
+        let row = self
+            .repo
+            .ensure_genesis(&genesis.chain_id, &derived_hash, &genesis.validators, &genesis.timestamp)
+            .await
+            .map_err(|e| io::Error::new(ErrorKind::ConnectionRefused, format!("{:?}", e)))?;
+
+        let chain_id: String = row.get("chain_id");
+        if chain_id != genesis.chain_id {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Database holds chain {} but config declares {}", chain_id, genesis.chain_id),
+            ));
+        }
+
+        let hash: String = row.get("hash");
+        Logger::console("genesis", &format!("chain={} genesis={}", chain_id, hash));
+        set_genesis(hash);
+        Ok(())
+    }
+
+    pub async fn restore_tip(&mut self) {
+        match self.repo.get_tip().await {
+            Ok(Some(row)) => {
+                let hash: String = row.get("hash");
+                let height: i64 = row.get("height");
+                Logger::console("genesis", &format!("Restored tip={} height={}", hash, height));
+                self.last_hash.set(hash, height);
+            }
+            Ok(None) => self.last_hash.set(genesis_hash(), 0),
+            Err(e) => Logger::console("genesis", &format!("Failed to restore tip: {:?}", e)),
+        }
+    }
+
+    pub async fn restore_stakes(&self) -> Option<StakeSnapshot> {
+        match self.repo.load_stakes().await {
+            Ok((rows, Some(seed))) => Some(StakeSnapshot {
+                nodes: rows.into_iter().map(|row| {
+                    let stake = StakeDTO::from_row(row);
+                    StakeEntry {
+                        url: stake.url,
+                        stake: stake.stake,
+                        reward: stake.reward,
+                        penalty: stake.penalty,
+                        public_key: stake.public_key,
+                        slashed: serde_json::from_str(&stake.slashed).unwrap_or_default(),
+                    }
+                }).collect(),
+                seed,
+            }),
+            Ok((_, None)) => None,
+            Err(e) => {
+                Logger::console("stake", &format!("Failed to load stakes: {:?}", e));
+                None
+            }
+        }
+    }
+
     pub async fn update_connections(
         &mut self,
         raw_connections: Vec<(String, bool)>,
@@ -67,3 +487,69 @@ impl AlterService {
         let _ = self.repo.update_connections(&connections).await;
     }
 }
+
+fn certified(blocks: &[ChainBlock], certificates: &[QuorumCertificate]) -> Vec<QuorumCertificate> {
+    let mut epochs: HashSet<u64> = HashSet::new();
+    certificates
+        .iter()
+        .filter(|certificate| {
+            let epoch_blocks: Vec<OrderedBlock> = blocks
+                .iter()
+                .filter(|block| block.epoch == certificate.epoch as i64)
+                .map(|block| {
+                    (
+                        (block.simple_hash.clone().unwrap_or_default(), block.organization.clone(), block.payload.clone()),
+                        block.created_at,
+                        Round::new(block.epoch as u64, block.slot as u64),
+                    )
+                })
+                .collect();
+            let members = blocks
+                .iter()
+                .find(|block| block.epoch == certificate.epoch as i64)
+                .and_then(|block| block.committee.clone())
+                .unwrap_or_default();
+            !epoch_blocks.is_empty() && digest_of(&epoch_blocks, &members) == certificate.digest
+        })
+        .filter(|certificate| epochs.insert(certificate.epoch))
+        .cloned()
+        .collect()
+}
+
+async fn report_failed(raw_blocks: &[OrderedBlock], reason: &str, discard: bool) {
+    let mut feedback_queue = FEEDBACK_QUEUE.lock().await;
+    for ((simple_hash_b64, _, _), _, _) in raw_blocks {
+        let url = if discard {
+            feedback_queue.get_block(simple_hash_b64)
+        } else {
+            feedback_queue.peek_block(simple_hash_b64)
+        };
+        if let Some(url) = url {
+            feedback_queue.reject(url, simple_hash_b64.clone(), reason.to_string());
+        }
+    }
+    drop(feedback_queue);
+}
+
+fn assign_merkle_roots(blocks: &mut [BlockDTO]) {
+    let mut start = 0;
+    while start < blocks.len() {
+        let round = (blocks[start].epoch, blocks[start].slot);
+        let end = blocks[start..]
+            .iter()
+            .position(|block| (block.epoch, block.slot) != round)
+            .map_or(blocks.len(), |offset| start + offset);
+
+        let leaves: Vec<[u8; 32]> = blocks[start..end]
+            .iter()
+            .map(|block| merkle::leaf_hash(&block.payload))
+            .collect();
+        let root = merkle::encode(&merkle::root(&leaves));
+
+        for (index, block) in blocks[start..end].iter_mut().enumerate() {
+            block.merkle_root = Some(root.clone());
+            block.leaf_index = Some(index as i64);
+        }
+        start = end;
+    }
+}
\ No newline at end of file
@@ -1,2 +1,6 @@
 pub mod alter_service;
-pub mod read_service;
\ No newline at end of file
+pub mod read_service;
+pub mod status_service;
+pub mod pending_service;
+pub mod verify_service;
+pub mod snapshot_service;
\ No newline at end of file
@@ -0,0 +1,86 @@
+use crate::dto::pending_dto::PendingBlockDTO;
+use crate::repo::alter_repo::AlterRepo;
+use common::logger::Logger;
+use common::memory::blocks_queue::BLOCKS_QUEUE;
+use common::memory::feedback_queue::FEEDBACK_QUEUE;
+use common::memory::pending_log::PENDING_LOG;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::time::{sleep, Duration};
+
+pub struct PendingService {
+    pub(crate) repo: AlterRepo,
+}
+
+impl PendingService {
+    pub async fn replay(&self, feedback_sender: &UnboundedSender<Vec<(String, String)>>) {
+        let rows = match self.repo.get_pending_blocks().await {
+            Ok(rows) => rows,
+            Err(e) => {
+                Logger::console("pending", &format!("Failed to load pending blocks: {:?}", e));
+                return;
+            }
+        };
+        let pending: Vec<PendingBlockDTO> = rows.into_iter().map(PendingBlockDTO::from_row).collect();
+
+        let mut queued = 0;
+        let mut committed: Vec<(String, String)> = Vec::new();
+        for block in pending {
+            if let Some(feedback) = block.feedback {
+                let mut feedback_queue = FEEDBACK_QUEUE.lock().await;
+                feedback_queue.add_block(block.hash.clone(), feedback);
+                drop(feedback_queue);
+            }
+
+            match block.full_hash {
+                Some(full_hash) => committed.push((block.hash, full_hash)),
+                None => {
+                    let mut blocks_queue = BLOCKS_QUEUE.lock().await;
+                    blocks_queue.restore(block.hash, block.organization, block.payload);
+                    drop(blocks_queue);
+                    queued += 1;
+                }
+            }
+        }
+
+        Logger::console(
+            "pending",
+            &format!("Replayed {} queued and {} undelivered blocks", queued, committed.len()),
+        );
+        if !committed.is_empty() {
+            let _ = feedback_sender.send(committed);
+        }
+    }
+
+    pub async fn power_journal(&mut self) {
+        let pending_log = PENDING_LOG.lock().await;
+        let flush = pending_log.flush_signal();
+        drop(pending_log);
+
+        loop {
+            tokio::select! {
+                _ = flush.notified() => {}
+                _ = sleep(Duration::from_millis(500)) => {}
+            }
+
+            let mut pending_log = PENDING_LOG.lock().await;
+            let (events, sequence) = pending_log.take_events();
+            drop(pending_log);
+            if events.is_empty() {
+                continue;
+            }
+
+            if let Err(e) = self.repo.apply_pending_events(&events).await {
+                Logger::console("pending", &format!("Failed to journal {} events: {:?}", events.len(), e));
+
+                let mut pending_log = PENDING_LOG.lock().await;
+                pending_log.requeue(events);
+                drop(pending_log);
+                continue;
+            }
+
+            let mut pending_log = PENDING_LOG.lock().await;
+            pending_log.mark_journaled(sequence);
+            drop(pending_log);
+        }
+    }
+}
\ No newline at end of file
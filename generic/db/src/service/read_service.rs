@@ -1,7 +1,17 @@
 use deadpool_postgres::PoolError;
 use crate::dto::block_dto::BlockDTO;
 use crate::dto::connection_dto::ConnectionDTO;
+use crate::dto::fork_dto::ForkDTO;
+use crate::dto::rollback_dto::RollbackDTO;
+use crate::dto::stake_dto::{CommitteeDTO, StakeDTO};
 use crate::repo::read_repo::ReadRepo;
+use crate::dto::proof_dto::ProofDTO;
+use crate::utils::merkle;
+use crate::dto::page_dto::PageDTO;
+use crate::dto::search_dto::SearchFilter;
+use crate::utils::cursor::{self, Cursor, KeyCursor, PairCursor, SequenceCursor};
+use chrono::{DateTime, Utc};
+use tokio_postgres::Row;
 
 pub struct ReadService {
     pub repo: ReadRepo,
@@ -9,14 +19,19 @@ pub struct ReadService {
 
 impl ReadService {
     
-    pub async fn get_page(&self, offset: i64) -> Result<Vec<BlockDTO>, PoolError> {
-        let rows = self.repo.get_page(offset).await?;
-        Ok(rows.into_iter().map(BlockDTO::from_row).collect())
+    pub async fn get_page(&self, cursor: Option<Cursor>, limit: i64) -> Result<PageDTO<BlockDTO>, PoolError> {
+        let rows = self.repo.get_page(cursor, limit + 1).await?;
+        Ok(block_page(rows, limit))
     }
     
-    pub async fn get_by_organization(&self, organization: String, offset: i64) -> Result<Vec<BlockDTO>, PoolError> {
-        let rows = self.repo.get_by_organization(organization, offset).await?;
-        Ok(rows.into_iter().map(BlockDTO::from_row).collect())
+    pub async fn get_by_organization(&self, organization: String, cursor: Option<Cursor>, limit: i64) -> Result<PageDTO<BlockDTO>, PoolError> {
+        let rows = self.repo.get_by_organization(organization, cursor, limit + 1).await?;
+        Ok(block_page(rows, limit))
+    }
+
+    pub async fn search(&self, filter: SearchFilter, cursor: Option<Cursor>, limit: i64) -> Result<PageDTO<BlockDTO>, PoolError> {
+        let rows = self.repo.search(&filter, cursor, limit + 1).await?;
+        Ok(block_page(rows, limit))
     }
 
     pub async fn get_connections(&self) -> Result<Vec<ConnectionDTO>, PoolError> {
@@ -24,6 +39,39 @@ impl ReadService {
         Ok(rows.into_iter().map(ConnectionDTO::from_row).collect())
     }
     
+    pub async fn get_forks(&self, cursor: Option<SequenceCursor>, limit: i64) -> Result<PageDTO<ForkDTO>, PoolError> {
+        let rows = self.repo.get_forks(cursor, limit + 1).await?;
+        Ok(row_page(rows, limit, ForkDTO::from_row, |row| {
+            cursor::encode(&row.get::<_, DateTime<Utc>>("detected_at"), &row.get::<_, i64>("id").to_string())
+        }))
+    }
+    
+    pub async fn get_rollbacks(&self, cursor: Option<SequenceCursor>, limit: i64) -> Result<PageDTO<RollbackDTO>, PoolError> {
+        let rows = self.repo.get_rollbacks(cursor, limit + 1).await?;
+        Ok(row_page(rows, limit, RollbackDTO::from_row, |row| {
+            cursor::encode(&row.get::<_, DateTime<Utc>>("reverted_at"), &row.get::<_, i64>("id").to_string())
+        }))
+    }
+    
+    pub async fn get_stakes(&self, cursor: Option<KeyCursor>, limit: i64) -> Result<PageDTO<StakeDTO>, PoolError> {
+        let rows = self.repo.get_stakes(cursor, limit + 1).await?;
+        Ok(row_page(rows, limit, StakeDTO::from_row, |row| {
+            cursor::encode_key(row.get::<_, i32>("stake") as i64, row.get("url"))
+        }))
+    }
+
+    pub async fn get_committees(&self, cursor: Option<PairCursor>, limit: i64) -> Result<PageDTO<CommitteeDTO>, PoolError> {
+        let rows = self.repo.get_committees(cursor, limit + 1).await?;
+        Ok(row_page(rows, limit, CommitteeDTO::from_row, |row| {
+            cursor::encode_key(row.get("epoch"), &row.get::<_, i64>("slot").to_string())
+        }))
+    }
+
+    pub async fn get_committees_by_epoch(&self, epoch: i64) -> Result<Vec<CommitteeDTO>, PoolError> {
+        let rows = self.repo.get_committees_by_epoch(epoch).await?;
+        Ok(rows.into_iter().map(CommitteeDTO::from_row).collect())
+    }
+    
     pub async fn get_by_hash(&self, hash: String) -> Result<Option<BlockDTO>, PoolError> {
         let row = self.repo.get_by_hash(hash).await?;
         if let Some(block) = row {
@@ -31,5 +79,90 @@ impl ReadService {
         }
         Ok(None)
     }
+
+    pub async fn get_by_height(&self, height: i64) -> Result<Option<BlockDTO>, PoolError> {
+        let row = self.repo.get_by_height(height).await?;
+        Ok(row.map(BlockDTO::from_row))
+    }
+
+    pub async fn get_successor(&self, hash: String) -> Result<Option<BlockDTO>, PoolError> {
+        let row = self.repo.get_successor(hash).await?;
+        Ok(row.map(BlockDTO::from_row))
+    }
+
+    pub async fn get_predecessor(&self, hash: String) -> Result<Option<BlockDTO>, PoolError> {
+        let row = self.repo.get_predecessor(hash).await?;
+        Ok(row.map(BlockDTO::from_row))
+    }
+
+    pub async fn get_ancestors(&self, hash: String, depth: i64) -> Result<Option<Vec<BlockDTO>>, PoolError> {
+        if self.repo.get_by_hash(hash.clone()).await?.is_none() {
+            return Ok(None);
+        }
+        let rows = self.repo.get_ancestors(hash, depth).await?;
+        Ok(Some(rows.into_iter().map(BlockDTO::from_row).collect()))
+    }
+
+    pub async fn get_descendants(&self, hash: String, depth: i64) -> Result<Option<Vec<BlockDTO>>, PoolError> {
+        if self.repo.get_by_hash(hash.clone()).await?.is_none() {
+            return Ok(None);
+        }
+        let rows = self.repo.get_descendants(hash, depth).await?;
+        Ok(Some(rows.into_iter().map(BlockDTO::from_row).collect()))
+    }
+
+    pub async fn get_proof(&self, hash: String) -> Result<Option<ProofDTO>, PoolError> {
+        let block = match self.get_by_hash(hash).await? {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+        let (merkle_root, leaf_index) = match (block.merkle_root, block.leaf_index) {
+            (Some(merkle_root), Some(leaf_index)) => (merkle_root, leaf_index),
+            _ => return Ok(None),
+        };
+
+        let rows = self.repo.get_merkle_leaves(merkle_root.clone(), block.epoch, block.slot).await?;
+        let leaves: Vec<[u8; 32]> = rows
+            .iter()
+            .map(|row| merkle::leaf_hash(row.get("payload")))
+            .collect();
+        let path = match merkle::proof(&leaves, leaf_index as usize) {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        Ok(Some(ProofDTO {
+            hash: block.hash,
+            leaf_hash: merkle::encode(&merkle::leaf_hash(&block.payload)),
+            leaf_index,
+            leaf_count: leaves.len() as i64,
+            merkle_root,
+            path,
+        }))
+    }
     
 }
+
+fn block_page(rows: Vec<Row>, limit: i64) -> PageDTO<BlockDTO> {
+    let mut items: Vec<BlockDTO> = rows.into_iter().map(BlockDTO::from_row).collect();
+    let next_cursor = if items.len() as i64 > limit {
+        items.truncate(limit as usize);
+        items.last().map(|block| cursor::encode(&block.created_at, &block.hash))
+    } else {
+        None
+    };
+    PageDTO { items, next_cursor }
+}
+
+fn row_page<T>(mut rows: Vec<Row>, limit: i64, from_row: fn(Row) -> T, cursor_of: fn(&Row) -> String) -> PageDTO<T> {
+    let next_cursor = if rows.len() as i64 > limit {
+        rows.truncate(limit as usize);
+        rows.last().map(cursor_of)
+    } else {
+        None
+    };
+    PageDTO {
+        items: rows.into_iter().map(from_row).collect(),
+        next_cursor,
+    }
+}
\ No newline at end of file
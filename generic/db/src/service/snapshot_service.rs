@@ -0,0 +1,202 @@
+use chrono::Utc;
+use deadpool_postgres::PoolError;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, ErrorKind, Write};
+use crate::dto::block_dto::BlockDTO;
+use crate::dto::snapshot_dto::{SnapshotManifest, SnapshotRecord, SNAPSHOT_VERSION};
+use crate::dto::stake_dto::{CommitteeDTO, StakeDTO};
+use crate::repo::alter_repo::AlterRepo;
+use crate::repo::read_repo::ReadRepo;
+use crate::utils::chain_hash;
+use common::payload;
+
+const IMPORT_CHUNK: usize = 1000;
+
+pub struct SnapshotService {
+    pub(crate) read_repo: ReadRepo,
+    pub(crate) alter_repo: AlterRepo,
+}
+
+fn db_error(e: PoolError) -> io::Error {
+    io::Error::new(ErrorKind::ConnectionRefused, format!("{:?}", e))
+}
+
+impl SnapshotService {
+
+    pub async fn export(&self, path: &str, with_history: bool) -> io::Result<SnapshotManifest> {
+        let genesis = self
+            .read_repo
+            .get_genesis()
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "Genesis is not initialised"))?;
+
+        let blocks: Vec<BlockDTO> = self
+            .read_repo
+            .get_chain_blocks()
+            .await
+            .map_err(db_error)?
+            .into_iter()
+            .map(BlockDTO::from_row)
+            .collect();
+
+        let (stakes, seed, committees) = if with_history {
+            let (rows, seed) = self.alter_repo.load_stakes().await.map_err(db_error)?;
+            let stakes: Vec<StakeDTO> = rows.into_iter().map(StakeDTO::from_row).collect();
+            let committees: Vec<CommitteeDTO> = self
+                .read_repo
+                .get_all_committees()
+                .await
+                .map_err(db_error)?
+                .into_iter()
+                .map(CommitteeDTO::from_row)
+                .collect();
+            (stakes, seed, committees)
+        } else {
+            (Vec::new(), None, Vec::new())
+        };
+
+        let genesis_hash: String = genesis.get("hash");
+        let (tip_hash, tip_height) = match blocks.last() {
+            Some(block) => (block.hash.clone(), block.height.unwrap_or(blocks.len() as i64)),
+            None => (genesis_hash.clone(), 0),
+        };
+        let manifest = SnapshotManifest {
+            version: SNAPSHOT_VERSION,
+            chain_id: genesis.get("chain_id"),
+            genesis_hash,
+            genesis_validators: genesis.get("validators"),
+            genesis_created_at: genesis.get("created_at"),
+            tip_hash,
+            tip_height,
+            blocks: blocks.len() as i64,
+            stakes: stakes.len() as i64,
+            committees: committees.len() as i64,
+            seed,
+            exported_at: Utc::now(),
+        };
+
+        let mut encoder = GzEncoder::new(BufWriter::new(File::create(path)?), Compression::default());
+        let mut write_record = |record: &SnapshotRecord| -> io::Result<()> {
+            serde_json::to_writer(&mut encoder, record)?;
+            encoder.write_all(b"\n")
+        };
+
+        write_record(&SnapshotRecord::Manifest(manifest.clone()))?;
+        for block in blocks {
+            write_record(&SnapshotRecord::Block(block))?;
+        }
+        for stake in stakes {
+            write_record(&SnapshotRecord::Stake(stake))?;
+        }
+        for committee in committees {
+            write_record(&SnapshotRecord::Committee(committee))?;
+        }
+        encoder.finish()?.flush()?;
+
+        Ok(manifest)
+    }
+
+    pub async fn import(&mut self, path: &str) -> io::Result<SnapshotManifest> {
+        let reader = BufReader::new(GzDecoder::new(BufReader::new(File::open(path)?)));
+
+        let mut manifest: Option<SnapshotManifest> = None;
+        let mut blocks: Vec<BlockDTO> = Vec::new();
+        let mut stakes: Vec<StakeDTO> = Vec::new();
+        let mut committees: Vec<CommitteeDTO> = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<SnapshotRecord>(&line)? {
+                SnapshotRecord::Manifest(record) if manifest.is_none() => manifest = Some(record),
+                SnapshotRecord::Manifest(_) => {
+                    return Err(io::Error::new(ErrorKind::InvalidData, "Snapshot has more than one manifest"));
+                }
+                _ if manifest.is_none() => {
+                    return Err(io::Error::new(ErrorKind::InvalidData, "Snapshot must start with its manifest"));
+                }
+                SnapshotRecord::Block(block) => blocks.push(block),
+                SnapshotRecord::Stake(stake) => stakes.push(stake),
+                SnapshotRecord::Committee(committee) => committees.push(committee),
+            }
+        }
+
+        let manifest = manifest.ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "Snapshot has no manifest"))?;
+        if manifest.version != SNAPSHOT_VERSION {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Unsupported snapshot version {}", manifest.version),
+            ));
+        }
+        if manifest.blocks != blocks.len() as i64
+            || manifest.stakes != stakes.len() as i64
+            || manifest.committees != committees.len() as i64
+        {
+            return Err(io::Error::new(ErrorKind::InvalidData, "Snapshot counts do not match its manifest"));
+        }
+        verify_links(&manifest, &blocks)?;
+
+        let genesis = self
+            .alter_repo
+            .ensure_genesis(
+                &manifest.chain_id,
+                &manifest.genesis_hash,
+                &manifest.genesis_validators,
+                &manifest.genesis_created_at,
+            )
+            .await
+            .map_err(db_error)?;
+        let chain_id: String = genesis.get("chain_id");
+        let genesis_hash: String = genesis.get("hash");
+        if chain_id != manifest.chain_id || genesis_hash != manifest.genesis_hash {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Database holds chain {} from {} but snapshot is {} from {}", chain_id, genesis_hash, manifest.chain_id, manifest.genesis_hash),
+            ));
+        }
+
+        for chunk in blocks.chunks(IMPORT_CHUNK) {
+            self.alter_repo.insert_many(chunk, &[], &[]).await.map_err(db_error)?;
+        }
+        if let Some(seed) = &manifest.seed {
+            self.alter_repo.save_stakes(&stakes, seed).await.map_err(db_error)?;
+        }
+        if !committees.is_empty() {
+            self.alter_repo.insert_committees(&committees).await.map_err(db_error)?;
+        }
+
+        Ok(manifest)
+    }
+}
+
+fn verify_links(manifest: &SnapshotManifest, blocks: &[BlockDTO]) -> io::Result<()> {
+    let mut previous_hash = manifest.genesis_hash.clone();
+    for (index, block) in blocks.iter().enumerate() {
+        let simple_hash = block
+            .simple_hash
+            .clone()
+            .unwrap_or_else(|| payload::payload_hash(&block.payload));
+        let recomputed = chain_hash::verify_block(&block.previous_hash, &block.hash, &simple_hash, &block.payload);
+        let height_matches = block.height.map(|height| height == index as i64 + 1).unwrap_or(true);
+        if block.previous_hash != previous_hash || !recomputed || !height_matches {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Snapshot chain breaks at {}", block.hash),
+            ));
+        }
+        previous_hash = block.hash.clone();
+    }
+
+    if previous_hash != manifest.tip_hash {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            format!("Snapshot ends at {} instead of tip {}", previous_hash, manifest.tip_hash),
+        ));
+    }
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,42 @@
+use common::memory::clock_skew::CLOCK_SKEW;
+use common::memory::blocks_queue::BLOCKS_QUEUE;
+use crate::dto::status_dto::{PendingDTO, PeerClockDTO, StatusDTO};
+
+pub struct StatusService;
+
+impl StatusService {
+
+    pub async fn get_status(&self) -> StatusDTO {
+        let blocks_queue = BLOCKS_QUEUE.lock().await;
+        let pending = blocks_queue
+            .get_depths()
+            .into_iter()
+            .map(|(organization, depth)| PendingDTO { organization, depth })
+            .collect();
+        drop(blocks_queue);
+
+        let clock_skew = CLOCK_SKEW.lock().await;
+        let peers = clock_skew
+            .get_samples()
+            .into_iter()
+            .map(|(url, sample)| PeerClockDTO {
+                url,
+                offset_ms: sample.offset_ms,
+                rtt_ms: sample.rtt_ms,
+                measured_at: sample.measured_at,
+            })
+            .collect();
+
+        let status = StatusDTO {
+            clock_offset_ms: clock_skew.estimate(),
+            max_clock_skew_ms: clock_skew.get_tolerance(),
+            within_tolerance: clock_skew.within_tolerance(),
+            peers,
+            pending,
+        };
+        drop(clock_skew);
+
+        status
+    }
+
+}
@@ -0,0 +1,126 @@
+use deadpool_postgres::PoolError;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use common::memory::last_hash::genesis_hash;
+use crate::dto::verify_dto::VerifyDTO;
+use crate::repo::read_repo::ReadRepo;
+use crate::utils::chain_hash;
+use common::payload;
+
+pub struct VerifyService {
+    pub repo: ReadRepo,
+}
+
+struct Link {
+    hash: String,
+    previous_hash: String,
+    simple_hash: Option<String>,
+    payload: String,
+    height: Option<i64>,
+}
+
+impl VerifyService {
+
+    pub async fn verify(&self, from: Option<String>) -> Result<Option<VerifyDTO>, PoolError> {
+        let links: Vec<Link> = self
+            .repo
+            .get_chain_links()
+            .await?
+            .into_iter()
+            .map(|row| Link {
+                hash: row.get("hash"),
+                previous_hash: row.get("previous_hash"),
+                simple_hash: row.get("simple_hash"),
+                payload: row.get("payload"),
+                height: row.get("height"),
+            })
+            .collect();
+
+        let genesis = genesis_hash();
+        let known: HashSet<&str> = links.iter().map(|link| link.hash.as_str()).collect();
+        let mut successors: HashMap<&str, Vec<&Link>> = HashMap::new();
+        let mut heights: BTreeMap<i64, usize> = BTreeMap::new();
+        let mut missing_predecessors: Vec<String> = Vec::new();
+        for link in &links {
+            successors.entry(link.previous_hash.as_str()).or_default().push(link);
+            if let Some(height) = link.height {
+                *heights.entry(height).or_default() += 1;
+            }
+            if link.previous_hash != genesis && !known.contains(link.previous_hash.as_str()) {
+                missing_predecessors.push(link.hash.clone());
+            }
+        }
+        let duplicate_heights: Vec<i64> = heights
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(height, _)| height)
+            .collect();
+
+        let (start, start_height) = match from {
+            None => (genesis.clone(), Some(0)),
+            Some(hash) => match links.iter().find(|link| link.hash == hash) {
+                Some(link) => (hash, link.height),
+                None => return Ok(None),
+            },
+        };
+
+        let mut checked = 0;
+        let mut derived = 0;
+        let mut first_break: Option<String> = None;
+        let mut break_reason: Option<String> = None;
+        let mut current = start.clone();
+        let mut current_height = start_height;
+        loop {
+            let next = match successors.get(current.as_str()).map(Vec::as_slice) {
+                None | Some([]) => break,
+                Some([next]) => *next,
+                Some(_) => {
+                    first_break = Some(current.clone());
+                    break_reason = Some("Multiple successors".to_string());
+                    break;
+                }
+            };
+
+            let payload_hash = payload::payload_hash(&next.payload);
+            match &next.simple_hash {
+                Some(simple_hash) if *simple_hash != payload_hash => {
+                    first_break = Some(next.hash.clone());
+                    break_reason = Some("Payload mismatch".to_string());
+                    break;
+                }
+                Some(_) => {}
+                None => derived += 1,
+            }
+            if chain_hash::recompute(&next.previous_hash, &payload_hash).as_deref() != Some(next.hash.as_str()) {
+                first_break = Some(next.hash.clone());
+                break_reason = Some("Hash mismatch".to_string());
+                break;
+            }
+            let expected_height = current_height.map(|height| height + 1);
+            if let (Some(height), Some(expected)) = (next.height, expected_height) {
+                if height != expected {
+                    first_break = Some(next.hash.clone());
+                    break_reason = Some(format!("Height {} where {} was expected", height, expected));
+                    break;
+                }
+            }
+
+            checked += 1;
+            current = next.hash.clone();
+            current_height = next.height.or(expected_height);
+        }
+
+        let valid = first_break.is_none() && missing_predecessors.is_empty() && duplicate_heights.is_empty();
+        Ok(Some(VerifyDTO {
+            start,
+            checked,
+            derived,
+            last_hash: current,
+            last_height: current_height,
+            first_break,
+            break_reason,
+            missing_predecessors,
+            duplicate_heights,
+            valid,
+        }))
+    }
+}
\ No newline at end of file
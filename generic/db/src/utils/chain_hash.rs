@@ -0,0 +1,38 @@
+use base64::{engine::general_purpose, Engine as _};
+use common::config::genesis_config::GenesisConfig;
+use common::payload;
+
+pub fn link_hash(previous_hash: &[u8], simple_hash: &[u8]) -> [u8; 64] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(previous_hash);
+    hasher.update(simple_hash);
+    let mut out = [0u8; 64];
+    hasher.finalize_xof().fill(&mut out);
+    out
+}
+
+pub fn recompute(previous_hash_b64: &str, simple_hash_b64: &str) -> Option<String> {
+    let previous_hash = general_purpose::URL_SAFE_NO_PAD.decode(previous_hash_b64).ok()?;
+    let simple_hash = general_purpose::STANDARD.decode(simple_hash_b64).ok()?;
+    Some(general_purpose::URL_SAFE_NO_PAD.encode(link_hash(&previous_hash, &simple_hash)))
+}
+
+pub fn verify_block(previous_hash_b64: &str, hash_b64: &str, simple_hash_b64: &str, block_payload: &str) -> bool {
+    payload::matches(simple_hash_b64, block_payload)
+        && recompute(previous_hash_b64, simple_hash_b64).as_deref() == Some(hash_b64)
+}
+
+pub fn genesis_block_hash(genesis: &GenesisConfig) -> String {
+    let mut validators = genesis.validators.clone();
+    validators.sort();
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(genesis.chain_id.as_bytes());
+    hasher.update(genesis.timestamp.to_rfc3339().as_bytes());
+    for validator in &validators {
+        hasher.update(validator.as_bytes());
+    }
+    let mut out = [0u8; 64];
+    hasher.finalize_xof().fill(&mut out);
+    general_purpose::URL_SAFE_NO_PAD.encode(out)
+}
\ No newline at end of file
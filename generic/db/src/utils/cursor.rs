@@ -0,0 +1,38 @@
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+
+pub type Cursor = (DateTime<Utc>, String);
+pub type SequenceCursor = (DateTime<Utc>, i64);
+pub type KeyCursor = (i64, String);
+pub type PairCursor = (i64, i64);
+
+pub fn encode(created_at: &DateTime<Utc>, hash: &str) -> String {
+    encode_key(created_at.timestamp_micros(), hash)
+}
+
+pub fn decode(cursor: &str) -> Option<Cursor> {
+    let (micros, hash) = decode_key(cursor)?;
+    let created_at = DateTime::from_timestamp_micros(micros)?;
+    Some((created_at, hash))
+}
+
+pub fn encode_key(key: i64, tie: &str) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(format!("{}|{}", key, tie))
+}
+
+pub fn decode_key(cursor: &str) -> Option<KeyCursor> {
+    let decoded = general_purpose::URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    let decoded = String::from_utf8(decoded).ok()?;
+    let (key, tie) = decoded.split_once('|')?;
+    Some((key.parse().ok()?, tie.to_string()))
+}
+
+pub fn decode_sequence(cursor: &str) -> Option<SequenceCursor> {
+    let (at, id) = decode(cursor)?;
+    Some((at, id.parse().ok()?))
+}
+
+pub fn decode_pair(cursor: &str) -> Option<PairCursor> {
+    let (key, tie) = decode_key(cursor)?;
+    Some((key, tie.parse().ok()?))
+}
\ No newline at end of file
@@ -0,0 +1,75 @@
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProofStep {
+    pub sibling: String,
+    pub position: String,
+}
+
+pub fn leaf_hash(payload: &str) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[LEAF_PREFIX]);
+    hasher.update(payload.as_bytes());
+    *hasher.finalize().as_bytes()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    *hasher.finalize().as_bytes()
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+pub fn root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return *blake3::hash(&[]).as_bytes();
+    }
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+pub fn proof(leaves: &[[u8; 32]], index: usize) -> Option<Vec<ProofStep>> {
+    if index >= leaves.len() {
+        return None;
+    }
+
+    let mut steps = Vec::new();
+    let mut level = leaves.to_vec();
+    let mut index = index;
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            steps.push(ProofStep {
+                sibling: encode(&level[sibling]),
+                position: if sibling < index { "left" } else { "right" }.to_string(),
+            });
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    Some(steps)
+}
+
+pub fn encode(hash: &[u8; 32]) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(hash)
+}
\ No newline at end of file
@@ -1 +1,5 @@
 pub mod cors_policy;
+
+pub mod merkle;
+pub mod chain_hash;
+pub mod cursor;
\ No newline at end of file
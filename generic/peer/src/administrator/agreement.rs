@@ -0,0 +1,365 @@
+use crate::administrator::interpreter::InterpreterConstruct;
+use crate::memory::manager_agreement::{ManagerAgreement, Proposal, MANAGER_AGREEMENT};
+use crate::memory::manager_client::MANAGER_CLIENT;
+use crate::memory::manager_network::MANAGER_NETWORK;
+use crate::utils::task_codes;
+use chrono::Utc;
+use common::certificate::{digest_of, QuorumCertificate};
+use common::logger::Logger;
+use common::memory::db_queue::DB_QUEUE;
+use common::memory::blocks_queue::BLOCKS_QUEUE;
+use common::round::Round;
+use std::collections::HashSet;
+use std::io::{Error, ErrorKind};
+use tokio::io;
+use tokio::sync::MutexGuard;
+
+async fn endorses(epoch: u64, (blocks, members): &Proposal) -> bool {
+    let db_queue = DB_QUEUE.lock().await;
+    let local = db_queue.get_epoch(epoch);
+    let local_members = db_queue.get_members(epoch);
+    drop(db_queue);
+
+    let blocks_queue = BLOCKS_QUEUE.lock().await;
+    let inserted = blocks
+        .iter()
+        .any(|block| !local.contains(block) && !blocks_queue.holds(&block.0));
+    drop(blocks_queue);
+
+    let proposed: HashSet<&String> = blocks.iter().map(|((hash, _, _), _, _)| hash).collect();
+    let censored = local.iter().any(|((hash, _, _), _, _)| !proposed.contains(hash));
+
+    !inserted && !censored && *members == local_members
+}
+
+pub async fn propose_epoch(round: Round) -> io::Result<()> {
+    let manager_network = MANAGER_NETWORK.lock().await;
+    let committee = manager_network.get_committee();
+    drop(manager_network);
+
+    let mut manager_agreement = MANAGER_AGREEMENT.lock().await;
+    let validators = manager_agreement.validators_for(committee);
+    manager_agreement.ensure_epoch(round.epoch, validators);
+    let server_url = manager_agreement.get_server_url();
+    if manager_agreement.is_decided() || manager_agreement.leader().as_deref() != Some(server_url.as_str()) {
+        return Ok(());
+    }
+    let view = manager_agreement.get_view();
+    let locked = manager_agreement.get_locked();
+    drop(manager_agreement);
+
+    let (digest, proposal) = match locked {
+        Some(locked) => locked,
+        None => {
+            let db_queue = DB_QUEUE.lock().await;
+            let blocks = db_queue.get_epoch(round.epoch);
+            let members = db_queue.get_members(round.epoch);
+            drop(db_queue);
+            (digest_of(&blocks, &members), (blocks, members))
+        }
+    };
+    let block_count = proposal.0.len();
+    let encoded_proposal = serde_json::to_string(&proposal)?;
+
+    let manager_agreement = MANAGER_AGREEMENT.lock().await;
+    let proposal = build_signed(
+        &manager_agreement,
+        task_codes::PROPOSE,
+        round,
+        (view.to_string(), digest.clone(), encoded_proposal),
+    )?;
+    drop(manager_agreement);
+
+    Logger::console(
+        "agreement",
+        &format!("{} view {} proposing {} blocks as {}", round, view, block_count, digest),
+    );
+
+    proposal.broadcast().await?;
+    proposal.apply_agreement().await
+}
+
+pub(crate) fn build_signed(
+    manager_agreement: &ManagerAgreement,
+    task: u16,
+    round: Round,
+    data: (String, String, String),
+) -> io::Result<InterpreterConstruct> {
+    let signer = manager_agreement
+        .get_signer()
+        .ok_or_else(|| Error::new(ErrorKind::NotFound, "No signing key"))?;
+
+    let mut construct = InterpreterConstruct {
+        creator: manager_agreement.get_server_url(),
+        from: manager_agreement.get_server_url(),
+        task,
+        data: Some(data),
+        created_at: Utc::now(),
+        round,
+        signature: None,
+        proof: None,
+    };
+    construct.sign(signer);
+    Ok(construct)
+}
+
+pub(crate) async fn verify_certificate(certificate: &QuorumCertificate) -> bool {
+    let manager_agreement = MANAGER_AGREEMENT.lock().await;
+    let validators = manager_agreement.validators_at(certificate.epoch);
+    drop(manager_agreement);
+
+    let validators = match validators {
+        Some(validators) => validators,
+        None => return false,
+    };
+
+    let mut voters: HashSet<String> = HashSet::new();
+    for vote in &certificate.votes {
+        if vote.round.epoch != certificate.epoch || !validators.contains(&vote.voter) || voters.contains(&vote.voter) {
+            continue;
+        }
+        let construct = InterpreterConstruct {
+            creator: vote.voter.clone(),
+            from: vote.voter.clone(),
+            task: task_codes::PRECOMMIT,
+            data: Some((certificate.view.to_string(), certificate.digest.clone(), String::new())),
+            created_at: Utc::now(),
+            round: vote.round,
+            signature: Some(vote.signature.clone()),
+            proof: None,
+        };
+        if construct.verify_creator().await.is_ok() {
+            voters.insert(vote.voter.clone());
+        }
+    }
+    voters.len() > (2 * validators.len()) / 3
+}
+
+pub async fn advance_view(round: Round) -> io::Result<bool> {
+    let manager_network = MANAGER_NETWORK.lock().await;
+    let current = manager_network.get_round();
+    drop(manager_network);
+
+    let mut manager_agreement = MANAGER_AGREEMENT.lock().await;
+    if current != round || manager_agreement.get_epoch() != round.epoch || manager_agreement.is_decided() {
+        return Ok(false);
+    }
+    let view = manager_agreement.advance_view();
+    let early = manager_agreement.proposal_at(view);
+    drop(manager_agreement);
+
+    Logger::console("agreement", &format!("{} timed out, moving to view {}", round, view));
+
+    if let Some((digest, proposal)) = early {
+        let endorsed = endorses(round.epoch, &proposal).await;
+
+        let mut manager_agreement = MANAGER_AGREEMENT.lock().await;
+        let server_url = manager_agreement.get_server_url();
+        let is_validator = manager_agreement.is_validator(&server_url);
+        let mut outgoing: Vec<InterpreterConstruct> = Vec::new();
+        if manager_agreement.get_view() == view
+            && is_validator
+            && manager_agreement.may_prevote(&digest, endorsed)
+            && manager_agreement.take_prevote()
+        {
+            outgoing.push(build_vote(&mut manager_agreement, task_codes::PREVOTE, round, view, digest)?);
+        }
+        settle(round, manager_agreement, is_validator, outgoing).await?;
+    }
+    Ok(true)
+}
+
+async fn settle(
+    round: Round,
+    mut manager_agreement: MutexGuard<'_, ManagerAgreement>,
+    is_validator: bool,
+    mut outgoing: Vec<InterpreterConstruct>,
+) -> io::Result<()> {
+    if is_validator {
+        if let Some(digest) = manager_agreement.prevote_quorum() {
+            if manager_agreement.take_precommit(&digest) {
+                let view = manager_agreement.get_view();
+                let vote = build_vote(&mut manager_agreement, task_codes::PRECOMMIT, round, view, digest)?;
+                outgoing.push(vote);
+            }
+        }
+    }
+
+    let decision = match manager_agreement.precommit_quorum() {
+        Some((view, digest)) => manager_agreement.decide(view, digest.clone()).map(|decision| (digest, decision)),
+        None => None,
+    };
+    drop(manager_agreement);
+
+    for vote in outgoing {
+        vote.broadcast().await?;
+    }
+
+    if let Some((digest, (blocks, header))) = decision {
+        Logger::console(
+            "agreement",
+            &format!("{} decided {} blocks as {}", round, blocks.len(), digest),
+        );
+
+        let committed: Vec<String> = blocks.iter().map(|((hash, _, _), _, _)| hash.clone()).collect();
+
+        let mut manager_network = MANAGER_NETWORK.lock().await;
+        manager_network.refresh_stakes(&header.2.iter().cloned().collect());
+        drop(manager_network);
+
+        let mut db_queue = DB_QUEUE.lock().await;
+        db_queue.set_decision(round.epoch, blocks, header);
+        drop(db_queue);
+
+        let mut blocks_queue = BLOCKS_QUEUE.lock().await;
+        blocks_queue.remove_committed(&committed);
+        drop(blocks_queue);
+    }
+
+    Ok(())
+}
+
+fn build_vote(
+    manager_agreement: &mut ManagerAgreement,
+    task: u16,
+    round: Round,
+    view: u64,
+    digest: String,
+) -> io::Result<InterpreterConstruct> {
+    let server_url = manager_agreement.get_server_url();
+    let vote = build_signed(manager_agreement, task, round, (view.to_string(), digest.clone(), String::new()))?;
+
+    manager_agreement.mark_seen(task, server_url.clone(), view);
+    if task == task_codes::PREVOTE {
+        manager_agreement.add_prevote(view, digest, server_url);
+    } else {
+        let signature = vote.signature.clone().unwrap_or_default();
+        manager_agreement.add_precommit(view, digest, server_url, round, signature);
+    }
+    Ok(vote)
+}
+
+impl InterpreterConstruct {
+    fn agreement_view(&self) -> io::Result<u64> {
+        match &self.data {
+            Some((view, _, _)) => view.parse::<u64>().map_err(|e| Error::new(ErrorKind::InvalidData, e)),
+            None => Err(Error::new(ErrorKind::InvalidData, "Couldn't agree for no data")),
+        }
+    }
+
+    pub(crate) async fn verify_creator(&self) -> io::Result<()> {
+        let manager_network = MANAGER_NETWORK.lock().await;
+        let public_key = manager_network.get_public_key(&self.creator);
+        drop(manager_network);
+
+        let public_key = public_key
+            .ok_or_else(|| Error::new(ErrorKind::PermissionDenied, "Unknown signer"))?;
+        if !self.verify(&public_key) {
+            return Err(Error::new(ErrorKind::PermissionDenied, "Invalid signature"));
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn broadcast(&self) -> io::Result<()> {
+        let manager_client = MANAGER_CLIENT.lock().await;
+        let all_clients = manager_client.get_to_send_clients(&self.from);
+        drop(manager_client);
+
+        let encoded_msg = self.serialize_to_vec()?;
+        self.action_senders(all_clients, encoded_msg).await;
+        Ok(())
+    }
+
+    pub(crate) async fn distribute_agreement(&self) -> io::Result<()> {
+        let view = self.agreement_view()?;
+        let manager_agreement = MANAGER_AGREEMENT.lock().await;
+        let seen = manager_agreement.get_epoch() == self.round.epoch
+            && manager_agreement.has_seen(self.task, &self.creator, view);
+        drop(manager_agreement);
+        if seen {
+            return Ok(());
+        }
+
+        self.verify_creator().await?;
+        self.broadcast().await
+    }
+
+    pub(crate) async fn apply_agreement(&self) -> io::Result<()> {
+        self.verify_creator().await?;
+
+        let view = self.agreement_view()?;
+        let (_, digest, encoded) = self
+            .data
+            .clone()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Couldn't agree for no data"))?;
+
+        let manager_network = MANAGER_NETWORK.lock().await;
+        let committee = manager_network.get_committee();
+        drop(manager_network);
+
+        let proposed = match self.task {
+            task_codes::PROPOSE => {
+                let proposal: Proposal = serde_json::from_str(&encoded)?;
+                let endorsed = endorses(self.round.epoch, &proposal).await;
+                Some((proposal, endorsed))
+            }
+            _ => None,
+        };
+
+        let mut manager_agreement = MANAGER_AGREEMENT.lock().await;
+        let validators = manager_agreement.validators_for(committee);
+        manager_agreement.ensure_epoch(self.round.epoch, validators);
+        if !manager_agreement.mark_seen(self.task, self.creator.clone(), view) {
+            return Ok(());
+        }
+
+        let server_url = manager_agreement.get_server_url();
+        let is_validator = manager_agreement.is_validator(&server_url);
+        let mut outgoing: Vec<InterpreterConstruct> = Vec::new();
+
+        match self.task {
+            task_codes::PROPOSE => {
+                if manager_agreement.leader_of(view).as_deref() != Some(self.creator.as_str()) {
+                    return Err(Error::new(ErrorKind::PermissionDenied, "Proposal not from leader"));
+                }
+                let ((blocks, members), endorsed) = proposed.unwrap_or_default();
+                if digest_of(&blocks, &members) != digest {
+                    return Err(Error::new(ErrorKind::InvalidData, "Proposal digest mismatch"));
+                }
+                if blocks.iter().any(|(_, _, round)| round.epoch != self.round.epoch) {
+                    return Err(Error::new(ErrorKind::InvalidData, "Proposal spans other epochs"));
+                }
+                let signature = self.signature.clone().unwrap_or_default();
+                manager_agreement.add_proposal(view, digest.clone(), (blocks, members), self.creator.clone(), signature);
+
+                if view == manager_agreement.get_view() {
+                    if !manager_agreement.may_prevote(&digest, endorsed) {
+                        Logger::console(
+                            "agreement",
+                            &format!("{} withholding prevote, {} adds, leaves out or replaces entries", self.round, digest),
+                        );
+                    } else if is_validator && manager_agreement.take_prevote() {
+                        let vote = build_vote(&mut manager_agreement, task_codes::PREVOTE, self.round, view, digest)?;
+                        outgoing.push(vote);
+                    }
+                }
+            }
+            task_codes::PREVOTE => {
+                if !manager_agreement.is_validator(&self.creator) {
+                    return Err(Error::new(ErrorKind::PermissionDenied, "Prevote not from validator"));
+                }
+                manager_agreement.add_prevote(view, digest, self.creator.clone());
+            }
+            task_codes::PRECOMMIT => {
+                if !manager_agreement.is_validator(&self.creator) {
+                    return Err(Error::new(ErrorKind::PermissionDenied, "Precommit not from validator"));
+                }
+                let signature = self.signature.clone().unwrap_or_default();
+                manager_agreement.add_precommit(view, digest, self.creator.clone(), self.round, signature);
+            }
+            _ => return Err(Error::new(ErrorKind::InvalidData, "Unknown agreement step")),
+        }
+
+        settle(self.round, manager_agreement, is_validator, outgoing).await
+    }
+}
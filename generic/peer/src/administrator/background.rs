@@ -4,6 +4,18 @@ use common::config::peer_config::PeerConfig;
 use crate::architecture::constructor::constructor_job;
 use crate::memory::manager_server::MANAGER_SERVER;
 use crate::utils::aes_encryption::AesEncryption;
+use crate::administrator::interpreter::InterpreterConstruct;
+use crate::communication::sender::write_node;
+use crate::memory::manager_client::MANAGER_CLIENT;
+use crate::memory::manager_network::MANAGER_NETWORK;
+use crate::utils::task_codes;
+use chrono::Utc;
+use common::logger::Logger;
+use common::memory::clock_skew::CLOCK_SKEW;
+use common::memory::chain_sync::CHAIN_SYNC;
+use common::memory::blocks_queue::BLOCKS_QUEUE;
+use crate::administrator::agreement::build_signed;
+use crate::memory::manager_agreement::MANAGER_AGREEMENT;
 
 pub async fn run_memory_jobs() {
     loop {
@@ -24,4 +36,148 @@ pub async fn run_constructor_job(
         constructor_job(&encryption, &peer_config, &out_server_url).await;
         sleep(Duration::from_secs(5)).await;
     }
-}
\ No newline at end of file
+}
+
+pub async fn run_heartbeat_job(out_server_url: String) {
+    loop {
+        sleep(Duration::from_secs(30)).await;
+
+        let manager_client = MANAGER_CLIENT.lock().await;
+        let connected_clients = manager_client.get_connected_clients();
+        drop(manager_client);
+
+        let manager_network = MANAGER_NETWORK.lock().await;
+        let round = manager_network.get_round();
+        drop(manager_network);
+
+        for client in connected_clients {
+            let sent_at = Utc::now().timestamp_millis();
+            let mut clock_skew = CLOCK_SKEW.lock().await;
+            clock_skew.probe(client.clone(), sent_at);
+            drop(clock_skew);
+
+            let interpreter_construct = InterpreterConstruct {
+                creator: out_server_url.clone(),
+                from: out_server_url.clone(),
+                task: task_codes::HEARTBEAT,
+                data: Some((
+                    "heartbeat".to_string(),
+                    sent_at.to_string(),
+                    client.clone(),
+                )),
+                created_at: Utc::now(),
+                round,
+                signature: None,
+                proof: None,
+            };
+
+            if let Ok(encoded_msg) = interpreter_construct.serialize_to_vec() {
+                let _ = write_node(&client, encoded_msg).await;
+            }
+        }
+
+        let clock_skew = CLOCK_SKEW.lock().await;
+        let estimate = clock_skew.estimate();
+        let within_tolerance = clock_skew.within_tolerance();
+        let tolerance_ms = clock_skew.get_tolerance();
+        drop(clock_skew);
+
+        if let (Some(offset_ms), false) = (estimate, within_tolerance) {
+            Logger::console(
+                "clock",
+                &format!("Network clock offset {}ms exceeds tolerance of {}ms", offset_ms, tolerance_ms),
+            );
+        }
+    }
+}
+
+pub async fn run_chain_sync_job() {
+    loop {
+        sleep(Duration::from_secs(2)).await;
+
+        let mut chain_sync = CHAIN_SYNC.lock().await;
+        let requests = chain_sync.take_outgoing_requests();
+        let responses = chain_sync.take_outgoing_responses();
+        drop(chain_sync);
+
+        let manager_network = MANAGER_NETWORK.lock().await;
+        let round = manager_network.get_round();
+        drop(manager_network);
+
+        let mut outgoing: Vec<(String, u16, String)> = Vec::new();
+        for request in requests {
+            match serde_json::to_string(&request) {
+                Ok(encoded) => outgoing.push((request.peer, task_codes::CHAIN_REQ, encoded)),
+                Err(e) => Logger::console("fork", &format!("Couldn't encode branch request: {:?}", e)),
+            }
+        }
+        for response in responses {
+            match serde_json::to_string(&response) {
+                Ok(encoded) => outgoing.push((response.peer, task_codes::RESP_CHAIN, encoded)),
+                Err(e) => Logger::console("fork", &format!("Couldn't encode branch: {:?}", e)),
+            }
+        }
+
+        for (peer, task, encoded) in outgoing {
+            let manager_agreement = MANAGER_AGREEMENT.lock().await;
+            let construct = build_signed(
+                &manager_agreement,
+                task,
+                round,
+                ("chain".to_string(), peer.clone(), encoded),
+            );
+            drop(manager_agreement);
+
+            let sent = match construct {
+                Ok(construct) => construct.route_chain().await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = sent {
+                Logger::console("fork", &format!("Couldn't send branch message to {}: {:?}", peer, e));
+            }
+        }
+    }
+}
+
+
+const MEMPOOL_CHUNK: usize = 200;
+
+pub async fn run_mempool_job() {
+    loop {
+        sleep(Duration::from_secs(1)).await;
+
+        let mut blocks_queue = BLOCKS_QUEUE.lock().await;
+        let unannounced = blocks_queue.take_unannounced();
+        drop(blocks_queue);
+        if unannounced.is_empty() {
+            continue;
+        }
+
+        let manager_network = MANAGER_NETWORK.lock().await;
+        let round = manager_network.get_round();
+        drop(manager_network);
+
+        for chunk in unannounced.chunks(MEMPOOL_CHUNK) {
+            let encoded_chunk = match serde_json::to_string(chunk) {
+                Ok(encoded_chunk) => encoded_chunk,
+                Err(_) => continue,
+            };
+            let manager_agreement = MANAGER_AGREEMENT.lock().await;
+            let interpreter_construct = build_signed(
+                &manager_agreement,
+                task_codes::MEMPOOL,
+                round,
+                ("mempool".to_string(), chunk.len().to_string(), encoded_chunk),
+            );
+            drop(manager_agreement);
+            let interpreter_construct = match interpreter_construct {
+                Ok(interpreter_construct) => interpreter_construct,
+                Err(_) => continue,
+            };
+
+            if let Err(e) = interpreter_construct.broadcast().await {
+                Logger::console("mempool", &format!("Couldn't gossip payloads: {:?}", e));
+            }
+        }
+    }
+}
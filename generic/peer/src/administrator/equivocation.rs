@@ -0,0 +1,156 @@
+use crate::administrator::agreement::build_signed;
+use crate::administrator::interpreter::InterpreterConstruct;
+use crate::memory::manager_agreement::MANAGER_AGREEMENT;
+use crate::memory::manager_network::MANAGER_NETWORK;
+use crate::utils::task_codes;
+use common::logger::Logger;
+use common::memory::db_queue::{BlockData, DB_QUEUE};
+use common::payload;
+use common::round::Round;
+use std::io::{Error, ErrorKind};
+use tokio::io;
+
+impl InterpreterConstruct {
+    fn as_signed_block(&self) -> InterpreterConstruct {
+        let mut block = self.clone();
+        block.task = task_codes::BLOCK;
+        block.from = block.creator.clone();
+        block
+    }
+
+    pub(crate) fn block_batch(&self) -> io::Result<Vec<BlockData>> {
+        match &self.data {
+            None => Ok(Vec::new()),
+            Some((kind, count, encoded_batch)) if kind == "batch" => {
+                let batch: Vec<BlockData> = serde_json::from_str(encoded_batch)?;
+                if count.parse::<usize>().ok() != Some(batch.len()) {
+                    return Err(Error::new(ErrorKind::InvalidData, "Batch count mismatch"));
+                }
+                if batch.iter().any(|(hash, _, data)| !payload::matches(hash, data)) {
+                    return Err(Error::new(ErrorKind::InvalidData, "Hash does not match payload"));
+                }
+                Ok(batch)
+            }
+            Some((hash, _, data)) if !payload::matches(hash, data) => {
+                Err(Error::new(ErrorKind::InvalidData, "Hash does not match payload"))
+            }
+            Some(data) => Ok(vec![data.clone()]),
+        }
+    }
+
+    pub(crate) async fn accept_block(&self) -> io::Result<()> {
+        self.verify_creator().await?;
+        let block = self.as_signed_block();
+
+        let mut manager_network = MANAGER_NETWORK.lock().await;
+        if manager_network.has_received(&self.creator, self.round) {
+            let first = manager_network.get_block(&self.creator, self.round);
+            drop(manager_network);
+
+            if let Some(first) = first {
+                let first = InterpreterConstruct::from_json(first)?;
+                if first.round == block.round && first.data != block.data {
+                    return block.report_equivocation(first).await;
+                }
+            }
+            return Ok(());
+        }
+        if manager_network.is_slashed(&self.creator, self.round) {
+            return Err(Error::new(ErrorKind::PermissionDenied, "Creator equivocated in this round"));
+        }
+
+        let batch = self.block_batch()?;
+        self.check_eligibility(&manager_network)?;
+        manager_network.admit_member(self.creator.clone(), self.round);
+        manager_network.mark_received(self.creator.clone(), self.round);
+        manager_network.record_block(self.creator.clone(), self.round, serde_json::to_string(&block)?);
+        drop(manager_network);
+
+        let mut db_queue = DB_QUEUE.lock().await;
+        db_queue.add_block(
+            self.creator.clone(),
+            batch,
+            self.created_at,
+            self.round,
+        );
+        drop(db_queue);
+
+        Ok(())
+    }
+
+    async fn report_equivocation(&self, first: InterpreterConstruct) -> io::Result<()> {
+        let manager_agreement = MANAGER_AGREEMENT.lock().await;
+        let evidence = build_signed(
+            &manager_agreement,
+            task_codes::EVIDENCE,
+            self.round,
+            (
+                "evidence".to_string(),
+                serde_json::to_string(&first)?,
+                serde_json::to_string(self)?,
+            ),
+        )?;
+        drop(manager_agreement);
+
+        evidence.apply_evidence().await?;
+        evidence.broadcast().await
+    }
+
+    async fn check_evidence(&self) -> io::Result<(String, Round)> {
+        let (_, first, second) = self
+            .data
+            .clone()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Couldn't slash for no evidence"))?;
+        let first = InterpreterConstruct::from_json(first)?;
+        let second = InterpreterConstruct::from_json(second)?;
+
+        if first.task != task_codes::BLOCK
+            || second.task != task_codes::BLOCK
+            || first.creator != second.creator
+            || first.round != second.round
+            || first.data == second.data
+        {
+            return Err(Error::new(ErrorKind::InvalidData, "Invalid equivocation evidence"));
+        }
+
+        first.verify_creator().await?;
+        second.verify_creator().await?;
+
+        Ok((first.creator, first.round))
+    }
+
+    pub(crate) async fn distribute_evidence(&self) -> io::Result<()> {
+        let (creator, round) = self.check_evidence().await?;
+
+        let manager_network = MANAGER_NETWORK.lock().await;
+        let slashed = manager_network.is_slashed(&creator, round);
+        drop(manager_network);
+        if slashed {
+            return Ok(());
+        }
+
+        self.broadcast().await
+    }
+
+    pub(crate) async fn apply_evidence(&self) -> io::Result<()> {
+        let (creator, round) = self.check_evidence().await?;
+
+        let mut manager_network = MANAGER_NETWORK.lock().await;
+        let newly_slashed = manager_network.slash(&creator, round);
+        drop(manager_network);
+        if !newly_slashed {
+            return Ok(());
+        }
+
+        Logger::console(
+            "equivocation",
+            &format!("{} slashed {} reported by {}", round, creator, self.creator),
+        );
+
+        let mut db_queue = DB_QUEUE.lock().await;
+        db_queue.remove_block(&creator, round);
+        drop(db_queue);
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -1,16 +1,26 @@
+use crate::administrator::agreement::{build_signed, verify_certificate};
 use crate::communication::sender::write_node;
+use crate::memory::manager_agreement::MANAGER_AGREEMENT;
 use crate::memory::manager_client::MANAGER_CLIENT;
 use crate::memory::manager_network::{ManagerNetwork, MANAGER_NETWORK};
+use crate::utils::rsa_encryption::RsaEncryption;
 use crate::utils::task_codes;
+use base64::{engine::general_purpose, Engine};
 use chrono::{DateTime, Utc};
-use common::memory::db_queue::DB_QUEUE;
 use futures::future::join_all;
 use serde::{Deserialize, Serialize};
 use std::cmp::max;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::io::{Error, ErrorKind};
 use tokio::{io, task};
 use common::memory::lazy_clients::LAZY_CLIENTS;
+use common::memory::clock_skew::CLOCK_SKEW;
+use common::memory::chain_tips::{ChainTip, CHAIN_TIPS};
+use common::memory::chain_sync::{ChainRequest, ChainResponse, CHAIN_SYNC};
+use common::round::Round;
+
+// Slots a message may lead or trail the local round, covering clock skew at slot boundaries.
+const ROUND_WINDOW: u64 = 1;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct InterpreterConstruct {
@@ -19,6 +29,13 @@ pub struct InterpreterConstruct {
     pub task: u16,
     pub data: Option<(String, String, String)>,
     pub created_at: DateTime<Utc>,
+    pub round: Round,
+
+    #[serde(default)]
+    pub signature: Option<String>,
+
+    #[serde(default)]
+    pub proof: Option<String>,
 }
 
 impl InterpreterConstruct {
@@ -34,11 +51,92 @@ impl InterpreterConstruct {
         serde_json::to_vec(&self)
     }
 
+    fn signed_task(&self) -> u16 {
+        match self.task {
+            task_codes::RESP_SYNC => task_codes::BLOCK,
+            task => task,
+        }
+    }
+
+    fn signing_bytes(&self) -> Vec<u8> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&self.signed_task().to_le_bytes());
+        hasher.update(&(self.creator.len() as u64).to_le_bytes());
+        hasher.update(self.creator.as_bytes());
+        hasher.update(&self.round.epoch.to_le_bytes());
+        hasher.update(&self.round.slot.to_le_bytes());
+        if let Some((first, second, third)) = &self.data {
+            for part in [first, second, third] {
+                hasher.update(&(part.len() as u64).to_le_bytes());
+                hasher.update(part.as_bytes());
+            }
+        }
+        hasher.finalize().as_bytes().to_vec()
+    }
+
+    pub fn sign(&mut self, rsa_encryption: &RsaEncryption) {
+        let signature = rsa_encryption.sign(&self.signing_bytes());
+        self.signature = Some(general_purpose::STANDARD.encode(signature));
+    }
+
+    pub fn verify(&self, public_pem: &str) -> bool {
+        let signature = match &self.signature {
+            Some(signature) => signature,
+            None => return false,
+        };
+        let signature = match general_purpose::STANDARD.decode(signature) {
+            Ok(signature) => signature,
+            Err(_) => return false,
+        };
+        let pkey = match RsaEncryption::parse_public(public_pem.as_bytes()) {
+            Ok(pkey) => pkey,
+            Err(_) => return false,
+        };
+        RsaEncryption::verify(&self.signing_bytes(), &signature, &pkey)
+    }
+
+    pub(crate) fn check_eligibility(&self, manager_network: &ManagerNetwork) -> io::Result<String> {
+        let proof = self
+            .proof
+            .clone()
+            .ok_or_else(|| Error::new(ErrorKind::PermissionDenied, "Missing eligibility proof"))?;
+        if !manager_network.check_member(&self.creator, self.round, &proof) {
+            return Err(Error::new(ErrorKind::PermissionDenied, "Not eligible for committee"));
+        }
+        Ok(proof)
+    }
+
+    pub fn check_round(&self, current: Round, slots_per_epoch: u64) -> io::Result<()> {
+        let distance = self
+            .round
+            .absolute(slots_per_epoch)
+            .abs_diff(current.absolute(slots_per_epoch));
+        let accepted = match self.task {
+            task_codes::RESP_SYNC => {
+                self.round.epoch == current.epoch && self.round.slot < current.slot
+            }
+            task_codes::HEARTBEAT | task_codes::RESP_HEARTBEAT => true,
+            task_codes::CHAIN_REQ | task_codes::RESP_CHAIN => true,
+            task_codes::EVIDENCE => self.round <= current || distance <= ROUND_WINDOW,
+            task_codes::MEMPOOL => true,
+            _ => distance <= ROUND_WINDOW,
+        };
+
+        if accepted {
+            return Ok(());
+        }
+        let kind = if self.round < current { "Stale" } else { "Future" };
+        Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("{} round {} for task {}, current {}", kind, self.round, self.task, current),
+        ))
+    }
+
     pub async fn apply(&self) -> io::Result<()> {
         match self.task {
             task_codes::MAINTAIN => {
                 let mut manager_network = MANAGER_NETWORK.lock().await;
-                manager_network.mark_received(self.creator.clone());
+                manager_network.mark_received(self.creator.clone(), self.round);
 
                 let received_network = self.data.clone();
                 if received_network.is_none() {
@@ -56,24 +154,7 @@ impl InterpreterConstruct {
                 Ok(())
             }
 
-            task_codes::BLOCK => {
-                let mut manager_network = MANAGER_NETWORK.lock().await;
-                if manager_network.has_received(&self.creator) {
-                    return Ok(());
-                }
-                manager_network.mark_received(self.creator.clone());
-                drop(manager_network);
-
-                let mut db_queue = DB_QUEUE.lock().await;
-                db_queue.add_block(
-                    self.creator.clone(),
-                    self.data.clone(),
-                    self.created_at.clone(),
-                );
-                drop(db_queue);
-
-                Ok(())
-            }
+            task_codes::BLOCK => self.accept_block().await,
 
             task_codes::SYNC => {
                 let data = self.data.clone();
@@ -82,25 +163,23 @@ impl InterpreterConstruct {
                 }
                 let (_, _, encoded_nodes) = data.unwrap();
 
-                let missing_nodes: HashSet<String> = serde_json::from_str(&encoded_nodes)?;
+                let known_members: BTreeSet<String> = serde_json::from_str(&encoded_nodes)?;
+
+                let manager_network = MANAGER_NETWORK.lock().await;
+                let committee_round = manager_network.get_committee_round();
+                let missing_blocks: Vec<String> = manager_network
+                    .unknown_members(&known_members)
+                    .into_iter()
+                    .filter_map(|node| manager_network.get_block(&node, committee_round))
+                    .collect();
+                drop(manager_network);
 
                 let mut handles = Vec::new();
 
-                let db_queue = DB_QUEUE.lock().await;
-                for node in missing_nodes {
-                    let node_block = db_queue.get_last(&node);
-                    if node_block.is_none() {
-                        continue;
-                    }
-                    let (node_block, created_at) = node_block.unwrap();
-
-                    let response_construct = InterpreterConstruct {
-                        creator: node,
-                        from: "Response".to_string(),
-                        task: task_codes::RESP_SYNC,
-                        data: node_block.clone(),
-                        created_at: created_at.clone(),
-                    };
+                for encoded_block in missing_blocks {
+                    let mut response_construct = InterpreterConstruct::from_json(encoded_block)?;
+                    response_construct.from = "Response".to_string();
+                    response_construct.task = task_codes::RESP_SYNC;
                     let encoded_response = response_construct.serialize_to_vec()?;
 
                     let client_clone = self.from.clone();
@@ -109,27 +188,139 @@ impl InterpreterConstruct {
                     });
                     handles.push(handle);
                 }
-                drop(db_queue);
 
                 let _ = join_all(handles).await;
                 Ok(())
             }
 
-            task_codes::RESP_SYNC => {
-                let mut manager_network = MANAGER_NETWORK.lock().await;
-                if manager_network.has_received(&self.creator) {
-                    return Ok(());
+            task_codes::RESP_SYNC => self.accept_block().await,
+
+            task_codes::EVIDENCE => self.apply_evidence().await,
+
+            task_codes::MEMPOOL => self.apply_mempool().await,
+
+            task_codes::PROPOSE | task_codes::PREVOTE | task_codes::PRECOMMIT => {
+                self.apply_agreement().await
+            }
+
+            task_codes::TIP => {
+                self.verify_creator().await?;
+
+                let data = self.data.clone();
+                if data.is_none() {
+                    return Err(Error::new(ErrorKind::InvalidData, "Couldn't compare for no tip"));
                 }
-                manager_network.mark_received(self.creator.clone());
+                let (_, _, encoded_tip) = data.unwrap();
+                let tip: ChainTip = serde_json::from_str(&encoded_tip)?;
+
+                let mut chain_tips = CHAIN_TIPS.lock().await;
+                chain_tips.add_peer_tip(self.creator.clone(), tip);
+                drop(chain_tips);
+                Ok(())
+            }
+
+            task_codes::HEARTBEAT => {
+                let manager_network = MANAGER_NETWORK.lock().await;
+                let round = manager_network.get_round();
                 drop(manager_network);
 
-                let mut db_queue = DB_QUEUE.lock().await;
-                db_queue.add_block(
-                    self.creator.clone(),
-                    self.data.clone(),
-                    self.created_at.clone(),
+                let (_, sent_at, client) = self
+                    .data
+                    .clone()
+                    .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Couldn't answer for no data"))?;
+                let manager_agreement = MANAGER_AGREEMENT.lock().await;
+                let response_construct = build_signed(
+                    &manager_agreement,
+                    task_codes::RESP_HEARTBEAT,
+                    round,
+                    (Utc::now().timestamp_millis().to_string(), sent_at, client),
                 );
-                drop(db_queue);
+                drop(manager_agreement);
+                let encoded_response = response_construct?.serialize_to_vec()?;
+
+                write_node(&self.from, encoded_response).await
+            }
+
+            task_codes::CHAIN_REQ => {
+                if !self.chain_target_reached().await {
+                    return Ok(());
+                }
+                self.verify_creator().await?;
+
+                let data = self.data.clone();
+                if data.is_none() {
+                    return Err(Error::new(ErrorKind::InvalidData, "Couldn't serve for no locator"));
+                }
+                let (_, _, encoded_request) = data.unwrap();
+                let mut request: ChainRequest = serde_json::from_str(&encoded_request)?;
+                request.peer = self.creator.clone();
+
+                let mut chain_sync = CHAIN_SYNC.lock().await;
+                chain_sync.receive_request(request);
+                drop(chain_sync);
+                Ok(())
+            }
+
+            task_codes::RESP_CHAIN => {
+                if !self.chain_target_reached().await {
+                    return Ok(());
+                }
+                self.verify_creator().await?;
+
+                let data = self.data.clone();
+                if data.is_none() {
+                    return Err(Error::new(ErrorKind::InvalidData, "Couldn't apply for no branch"));
+                }
+                let (_, _, encoded_response) = data.unwrap();
+                let mut response: ChainResponse = serde_json::from_str(&encoded_response)?;
+                response.peer = self.creator.clone();
+
+                let mut certificates = Vec::with_capacity(response.certificates.len());
+                let mut epochs: HashSet<u64> = HashSet::new();
+                for certificate in response.certificates.drain(..) {
+                    if epochs.contains(&certificate.epoch) {
+                        continue;
+                    }
+                    if verify_certificate(&certificate).await {
+                        epochs.insert(certificate.epoch);
+                        certificates.push(certificate);
+                    }
+                }
+                response.certificates = certificates;
+
+                let mut chain_sync = CHAIN_SYNC.lock().await;
+                chain_sync.receive_response(response);
+                drop(chain_sync);
+                Ok(())
+            }
+
+            task_codes::RESP_HEARTBEAT => {
+                let received_at = Utc::now().timestamp_millis();
+                let data = self.data.clone();
+                if data.is_none() {
+                    return Err(Error::new(ErrorKind::InvalidData, "Couldn't measure for no data"));
+                }
+                let (peer_time, sent_at, peer) = data.unwrap();
+                if peer != self.creator {
+                    return Err(Error::new(ErrorKind::PermissionDenied, "Heartbeat answered by another peer"));
+                }
+                self.verify_creator().await?;
+                let peer_time = peer_time
+                    .parse::<i64>()
+                    .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+                let sent_at = sent_at
+                    .parse::<i64>()
+                    .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+                let offset_ms = peer_time - (sent_at + received_at) / 2;
+
+                let mut clock_skew = CLOCK_SKEW.lock().await;
+                if !clock_skew.answer_probe(&peer, sent_at) {
+                    drop(clock_skew);
+                    return Err(Error::new(ErrorKind::InvalidData, "Unsolicited heartbeat response"));
+                }
+                clock_skew.record(peer, offset_ms, received_at - sent_at);
+                drop(clock_skew);
                 Ok(())
             }
 
@@ -137,6 +328,64 @@ impl InterpreterConstruct {
         }
     }
 
+    fn chain_target(&self) -> io::Result<String> {
+        match &self.data {
+            Some((_, target, _)) => Ok(target.clone()),
+            None => Err(Error::new(ErrorKind::InvalidData, "Couldn't route for no target")),
+        }
+    }
+
+    async fn chain_target_reached(&self) -> bool {
+        let manager_agreement = MANAGER_AGREEMENT.lock().await;
+        let server_url = manager_agreement.get_server_url();
+        drop(manager_agreement);
+        if self.chain_target().ok().as_deref() != Some(server_url.as_str()) {
+            return false;
+        }
+
+        let mut chain_sync = CHAIN_SYNC.lock().await;
+        let first = chain_sync.mark_relayed(self.signature.clone().unwrap_or_default());
+        drop(chain_sync);
+        first
+    }
+
+    pub(crate) async fn route_chain(&self) -> io::Result<()> {
+        let target = self.chain_target()?;
+        let mut chain_sync = CHAIN_SYNC.lock().await;
+        chain_sync.mark_relayed(self.signature.clone().unwrap_or_default());
+        drop(chain_sync);
+
+        let manager_client = MANAGER_CLIENT.lock().await;
+        let connected = manager_client.contains(&target);
+        drop(manager_client);
+
+        if connected {
+            write_node(&target, self.serialize_to_vec()?).await
+        } else {
+            self.broadcast().await
+        }
+    }
+
+    async fn relay_chain(&self) -> io::Result<()> {
+        let target = self.chain_target()?;
+        let manager_agreement = MANAGER_AGREEMENT.lock().await;
+        let server_url = manager_agreement.get_server_url();
+        drop(manager_agreement);
+        if target == server_url {
+            return Ok(());
+        }
+
+        let mut chain_sync = CHAIN_SYNC.lock().await;
+        let first = chain_sync.mark_relayed(self.signature.clone().unwrap_or_default());
+        drop(chain_sync);
+        if !first {
+            return Ok(());
+        }
+
+        self.verify_creator().await?;
+        self.route_chain().await
+    }
+
     pub async fn distribute(&self, max_connections: u32) -> io::Result<()> {
         match self.task {
             task_codes::MAINTAIN => {
@@ -162,10 +411,27 @@ impl InterpreterConstruct {
 
             task_codes::BLOCK => {
                 let manager_network = MANAGER_NETWORK.lock().await;
-                if manager_network.has_received(&self.creator) {
-                    return Ok(());
+                if manager_network.has_received(&self.creator, self.round) {
+                    let first = manager_network.get_block(&self.creator, self.round);
+                    let slashed = manager_network.is_slashed(&self.creator, self.round);
+                    drop(manager_network);
+
+                    let conflicting = match first {
+                        Some(first) => InterpreterConstruct::from_json(first)?.data != self.data,
+                        None => false,
+                    };
+                    if !conflicting || slashed {
+                        return Ok(());
+                    }
+                    self.verify_creator().await?;
+                } else {
+                    drop(manager_network);
+
+                    self.verify_creator().await?;
+                    let manager_network = MANAGER_NETWORK.lock().await;
+                    self.check_eligibility(&manager_network)?;
+                    drop(manager_network);
                 }
-                drop(manager_network);
 
                 let manager_client = MANAGER_CLIENT.lock().await;
                 let selection_size = max((2 * max_connections) / 3, 1);
@@ -195,11 +461,42 @@ impl InterpreterConstruct {
 
             task_codes::RESP_SYNC => Ok(()),
 
+            task_codes::HEARTBEAT | task_codes::RESP_HEARTBEAT => Ok(()),
+
+            task_codes::CHAIN_REQ | task_codes::RESP_CHAIN => self.relay_chain().await,
+
+            task_codes::EVIDENCE => self.distribute_evidence().await,
+
+            task_codes::MEMPOOL => self.distribute_mempool(max_connections).await,
+
+            task_codes::PROPOSE | task_codes::PREVOTE | task_codes::PRECOMMIT => {
+                self.distribute_agreement().await
+            }
+
+            task_codes::TIP => {
+                let tip_epoch = match &self.data {
+                    Some((_, tip_epoch, _)) => tip_epoch
+                        .parse::<u64>()
+                        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?,
+                    None => return Err(Error::new(ErrorKind::InvalidData, "Couldn't compare for no tip")),
+                };
+
+                let chain_tips = CHAIN_TIPS.lock().await;
+                let seen = chain_tips.has_seen(&self.creator, tip_epoch);
+                drop(chain_tips);
+                if seen {
+                    return Ok(());
+                }
+
+                self.verify_creator().await?;
+                self.broadcast().await
+            }
+
             _ => Err(Error::new(ErrorKind::Other, "Unknown task")),
         }
     }
 
-    async fn action_senders(&self, selection: Vec<String>, msg: Vec<u8>) {
+    pub(crate) async fn action_senders(&self, selection: Vec<String>, msg: Vec<u8>) {
         let mut handles = Vec::new();
         for client in selection {
             if client == self.creator || client == self.from {
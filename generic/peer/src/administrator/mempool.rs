@@ -0,0 +1,81 @@
+use crate::administrator::agreement::build_signed;
+use crate::administrator::interpreter::InterpreterConstruct;
+use crate::memory::manager_agreement::MANAGER_AGREEMENT;
+use crate::memory::manager_client::MANAGER_CLIENT;
+use crate::utils::task_codes;
+use common::memory::blocks_queue::BLOCKS_QUEUE;
+use common::memory::db_queue::BlockData;
+use common::memory::pending_log::PENDING_LOG;
+use common::payload;
+use std::cmp::max;
+use std::io::{Error, ErrorKind};
+use tokio::io;
+
+impl InterpreterConstruct {
+    fn mempool_entries(&self) -> io::Result<Vec<BlockData>> {
+        let (_, _, encoded_entries) = self
+            .data
+            .clone()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Couldn't pool for no payloads"))?;
+        let entries: Vec<BlockData> = serde_json::from_str(&encoded_entries)?;
+        Ok(entries
+            .into_iter()
+            .filter(|(hash, _, data)| payload::matches(hash, data))
+            .collect())
+    }
+
+    pub(crate) async fn distribute_mempool(&self, max_connections: u32) -> io::Result<()> {
+        self.verify_creator().await?;
+        let entries = self.mempool_entries()?;
+
+        let blocks_queue = BLOCKS_QUEUE.lock().await;
+        let fresh: Vec<BlockData> = entries
+            .into_iter()
+            .filter(|(hash, _, _)| !blocks_queue.is_known(hash))
+            .collect();
+        drop(blocks_queue);
+        if fresh.is_empty() {
+            return Ok(());
+        }
+
+        let manager_agreement = MANAGER_AGREEMENT.lock().await;
+        let relayed = build_signed(
+            &manager_agreement,
+            task_codes::MEMPOOL,
+            self.round,
+            ("mempool".to_string(), fresh.len().to_string(), serde_json::to_string(&fresh)?),
+        )?;
+        drop(manager_agreement);
+
+        let manager_client = MANAGER_CLIENT.lock().await;
+        let selection_size = max((2 * max_connections) / 3, 1);
+        let selection = manager_client.get_random_selection(selection_size, &self.from);
+        drop(manager_client);
+
+        let encoded_msg = relayed.serialize_to_vec()?;
+        relayed.action_senders(selection, encoded_msg).await;
+        Ok(())
+    }
+
+    pub(crate) async fn apply_mempool(&self) -> io::Result<()> {
+        self.verify_creator().await?;
+        let entries = self.mempool_entries()?;
+
+        let mut blocks_queue = BLOCKS_QUEUE.lock().await;
+        let added: Vec<BlockData> = entries
+            .into_iter()
+            .filter(|(hash, organization, payload)| {
+                blocks_queue.add_gossiped(hash.clone(), organization.clone(), payload.clone())
+            })
+            .collect();
+        drop(blocks_queue);
+
+        let mut pending_log = PENDING_LOG.lock().await;
+        for (hash, organization, payload) in added {
+            pending_log.record_accepted(hash, organization, payload, None);
+        }
+        drop(pending_log);
+
+        Ok(())
+    }
+}
\ No newline at end of file
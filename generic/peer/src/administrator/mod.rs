@@ -1,3 +1,6 @@
 pub mod interpreter;
 pub mod background;
-pub mod synchronizer;
\ No newline at end of file
+pub mod synchronizer;
+pub mod agreement;
+pub mod equivocation;
+pub mod mempool;
\ No newline at end of file
@@ -1,88 +1,139 @@
+use crate::administrator::agreement::{advance_view, build_signed, propose_epoch};
+use crate::memory::manager_agreement::MANAGER_AGREEMENT;
+use common::memory::chain_tips::CHAIN_TIPS;
+use std::time::Duration;
+use tokio::io;
+use tokio::task;
+use tokio::time::sleep;
 use crate::administrator::interpreter::InterpreterConstruct;
 use crate::memory::manager_network::MANAGER_NETWORK;
 use crate::utils::task_codes;
+use crate::utils::vrf;
 use chrono::Utc;
 use common::logger::Logger;
 use common::memory::blocks_queue::BLOCKS_QUEUE;
-use tokio::time::{sleep, Duration};
-use common::memory::feedback_queue::FEEDBACK_QUEUE;
+use common::memory::bootstrap::BOOTSTRAP;
+use common::memory::last_hash::genesis_hash;
+use common::memory::db_queue::BlockData;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use common::memory::clock_skew::CLOCK_SKEW;
+use common::memory::stake_table::STAKE_TABLE;
+use common::config::scheduler_config::Phase;
+use common::round::Round;
+use common::scheduler::Tick;
 
 pub struct Synchronizer {
     server_url: String,
-    committee_size: u32,
     selection_size: u32,
+    max_batch_blocks: u32,
+    max_batch_bytes: u64,
+    view_timeout_ms: u32,
     first_maintenance: bool,
 }
 
 impl Synchronizer {
-    pub async fn new(server_url: String, committee_size: u32, selection_size: u32) -> Synchronizer {
+    pub async fn new(
+        server_url: String,
+        committee_size: u32,
+        selection_size: u32,
+        max_batch_blocks: u32,
+        max_batch_bytes: u64,
+        view_timeout_ms: u32,
+        public_key: String,
+    ) -> Synchronizer {
         let mut manager_network = MANAGER_NETWORK.lock().await;
-        manager_network.insert_node(server_url.clone(), 50, 0, 0);
+        let initial_stake = manager_network.initial_stake();
+        manager_network.insert_node(server_url.clone(), initial_stake, 0, 0, public_key);
+        manager_network.set_committee_size(committee_size);
         drop(manager_network);
 
         Synchronizer {
             server_url,
-            committee_size,
             selection_size,
+            max_batch_blocks,
+            max_batch_bytes,
+            view_timeout_ms,
             first_maintenance: true,
         }
     }
 
-    pub async fn power_synchronizer(&mut self) {
-        let mut last_seconds: i64 = 0;
+    pub async fn power_synchronizer(&mut self, mut ticks: broadcast::Receiver<Tick>) {
         loop {
-            let now = Utc::now();
-            let seconds = now.timestamp() % 12;
-            let nanos = now.timestamp_subsec_nanos();
-
-            let remaining_secs = (12 - seconds) % 12;
-            let total_nanos = remaining_secs * 1_000_000_000 - nanos as i64;
-            let wait_duration = Duration::from_nanos(total_nanos.max(0) as u64);
+            let tick = match ticks.recv().await {
+                Ok(tick) => tick,
+                Err(RecvError::Lagged(skipped)) => {
+                    Logger::console("synchronizer", &format!("Missed {} ticks", skipped));
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
 
-            let safe_sleep = wait_duration.saturating_sub(Duration::from_millis(200));
-            sleep(safe_sleep).await;
+            let round = tick.round;
+            let mut stake_table = STAKE_TABLE.lock().await;
+            let restored = stake_table.take_restored();
+            drop(stake_table);
 
-            loop {
-                let now = Utc::now();
-                if now.timestamp() % 12 == 0 && last_seconds < now.timestamp() {
-                    last_seconds = now.timestamp();
-                    break;
-                }
+            let mut manager_network = MANAGER_NETWORK.lock().await;
+            if let Some(snapshot) = restored {
+                Logger::console(
+                    "synchronizer",
+                    &format!("Restored stake of {} nodes", snapshot.nodes.len()),
+                );
+                manager_network.restore(snapshot);
             }
+            manager_network.set_round(round, tick.slots_per_epoch);
+            drop(manager_network);
 
-            let epoch = now.timestamp() % 60;
-            match epoch {
-                0 => {
-                    self.maintenance_round().await;
-                }
-                12 => {
-                    if !self.first_maintenance {
-                        self.committee_round().await;
-                    }
+            self.reseed(round).await;
+
+            match tick.phase {
+                Phase::Maintenance => {
+                    self.maintenance_round(round).await;
                 }
-                24 => {
+                Phase::Committee => {
                     if !self.first_maintenance {
-                        self.sync_round().await;
+                        self.committee_round(round).await;
                     }
                 }
-                36 => {
+                Phase::Sync => {
                     if !self.first_maintenance {
-                        self.committee_round().await;
+                        self.sync_round(round).await;
                     }
                 }
-                48 => {
+                Phase::Agreement => {
                     if !self.first_maintenance {
-                        self.sync_round().await;
+                        self.agreement_round(round).await;
                     }
                 }
-                _ => {}
             }
+        }
+    }
+
+    // Seeds come from the tip committed two epochs earlier, known to every node before the epoch starts.
+    async fn reseed(&self, round: Round) {
+        let chain_tips = CHAIN_TIPS.lock().await;
+        let seeds: Vec<(u64, String)> = [round.epoch, round.epoch + 1]
+            .into_iter()
+            .map(|epoch| {
+                let tip_hash = epoch
+                    .checked_sub(2)
+                    .and_then(|seed_epoch| chain_tips.get_local_until(seed_epoch))
+                    .map(|tip| tip.hash)
+                    .unwrap_or_else(genesis_hash);
+                (epoch, tip_hash)
+            })
+            .collect();
+        drop(chain_tips);
 
-            sleep(Duration::from_millis(1)).await;
+        let mut manager_network = MANAGER_NETWORK.lock().await;
+        for (epoch, tip_hash) in seeds {
+            manager_network.set_seed(epoch, &tip_hash);
         }
+        drop(manager_network);
     }
 
-    async fn maintenance_round(&mut self) {
+    async fn maintenance_round(&mut self, round: Round) {
         let mut manager_network = MANAGER_NETWORK.lock().await;
         manager_network.prepare_for_maintenance();
         let encoded_network = manager_network.serialize();
@@ -94,6 +145,9 @@ impl Synchronizer {
             task: task_codes::MAINTAIN,
             data: Some(("maintenance".to_string(), "job".to_string(), encoded_network)),
             created_at: Utc::now(),
+            round,
+            signature: None,
+            proof: None,
         };
 
         if let Ok(()) = interpreter_construct.distribute(self.selection_size).await {
@@ -103,13 +157,33 @@ impl Synchronizer {
         }
     }
 
-    async fn committee_round(&self) {
+    async fn committee_round(&self, round: Round) {
+        let bootstrap = BOOTSTRAP.lock().await;
+        let syncing = bootstrap.is_syncing();
+        drop(bootstrap);
+        if syncing {
+            Logger::console("synchronizer", &format!("{} skipping committee, chain still bootstrapping", round));
+            return;
+        }
+
         let mut manager_network = MANAGER_NETWORK.lock().await;
+        manager_network.open_committee(round);
+        let vrf_input = manager_network.vrf_input(round);
+        drop(manager_network);
 
-        manager_network.choose_committee(self.committee_size);
-        let committee = manager_network.get_committee();
+        let manager_agreement = MANAGER_AGREEMENT.lock().await;
+        let eligibility = manager_agreement
+            .get_signer()
+            .map(|signer| vrf::prove(signer, &vrf_input));
+        drop(manager_agreement);
 
-        Logger::console("synchronizer", &format!("committee={:?}", committee));
+        let manager_network = MANAGER_NETWORK.lock().await;
+        let proof = match eligibility {
+            Some((proof, output)) if manager_network.is_eligible(&self.server_url, &output) => Some(proof),
+            _ => None,
+        };
+
+        Logger::console("synchronizer", &format!("{} eligible={}", round, proof.is_some()));
         Logger::console(
             "synchronizer",
             &format!("network={:?}", manager_network.get_all_nodes()),
@@ -117,55 +191,137 @@ impl Synchronizer {
         
         drop(manager_network);
 
-        if committee.contains(&self.server_url) {
+        if proof.is_some() {
+            let clock_skew = CLOCK_SKEW.lock().await;
+            let within_tolerance = clock_skew.within_tolerance();
+            let estimate = clock_skew.estimate();
+            drop(clock_skew);
+            if !within_tolerance {
+                Logger::console(
+                    "synchronizer",
+                    &format!("{} skipping committee, clock offset {:?}ms", round, estimate),
+                );
+                return;
+            }
+
             let mut blocks_queue = BLOCKS_QUEUE.lock().await;
-            let block_data = blocks_queue.get_block();
+            let batch: Vec<BlockData> = blocks_queue.get_batch(self.max_batch_blocks, self.max_batch_bytes);
             drop(blocks_queue);
-            let data = match block_data {
-                Some((hash, organization, feedback, payload)) => {
-                    let mut feedback_queue = FEEDBACK_QUEUE.lock().await;
-                    feedback_queue.add_block(hash.clone(), feedback);
-                    drop(feedback_queue);
-                    Some((hash, organization, payload))
-                },
-                None => None,
+
+            Logger::console("synchronizer", &format!("{} proposing batch of {}", round, batch.len()));
+            let data = if batch.is_empty() {
+                None
+            } else {
+                Some((
+                    "batch".to_string(),
+                    batch.len().to_string(),
+                    serde_json::to_string(&batch).unwrap(),
+                ))
             };
 
-            let interpreter_construct = InterpreterConstruct {
+            let mut interpreter_construct = InterpreterConstruct {
                 creator: self.server_url.clone(),
                 from: self.server_url.clone(),
                 task: task_codes::BLOCK,
                 data,
                 created_at: Utc::now(),
+                round,
+                signature: None,
+                proof,
             };
 
+            let manager_agreement = MANAGER_AGREEMENT.lock().await;
+            if let Some(signer) = manager_agreement.get_signer() {
+                interpreter_construct.sign(signer);
+            }
+            drop(manager_agreement);
+
             if let Err(e) = interpreter_construct.distribute(self.selection_size).await {
-                println!("Failed to distribute interpreter construct: {:?}", e);
+                println!("Failed to distribute interpreter construct for {}: {:?}", round, e);
             }
 
             if let Err(e) = interpreter_construct.apply().await {
-                println!("Failed to apply interpreter construct: {:?}", e);
+                println!("Failed to apply interpreter construct for {}: {:?}", round, e);
             }
         }
     }
 
-    async fn sync_round(&self) {
+    async fn sync_round(&self, round: Round) {
         let manager_network = MANAGER_NETWORK.lock().await;
-        let missing_nodes = manager_network.missing_nodes();
+        let committee = manager_network.get_committee();
+        let snapshot = manager_network.snapshot();
+        let committee_record = manager_network.committee_record();
         drop(manager_network);
 
-        let encoded_missing_nodes = serde_json::to_string(&missing_nodes).unwrap();
+        let mut stake_table = STAKE_TABLE.lock().await;
+        stake_table.publish(snapshot, committee_record);
+        drop(stake_table);
+
+        Logger::console("synchronizer", &format!("{} committee={:?}", round, committee));
+
+        let encoded_known_members = serde_json::to_string(&committee).unwrap();
 
         let interpreter_construct = InterpreterConstruct {
             creator: self.server_url.clone(),
             from: self.server_url.clone(),
             task: task_codes::SYNC,
-            data: Some(("missing".to_string(), "job".to_string(), encoded_missing_nodes)),
+            data: Some(("known".to_string(), "job".to_string(), encoded_known_members)),
             created_at: Utc::now(),
+            round,
+            signature: None,
+            proof: None,
         };
 
         if let Err(e) = interpreter_construct.distribute(self.selection_size).await {
-            println!("Failed to distribute SYNC interpreter construct: {:?}", e);
+            println!("Failed to distribute SYNC interpreter construct for {}: {:?}", round, e);
         }
+
+        if let Err(e) = self.announce_tip(round).await {
+            println!("Failed to announce chain tip for {}: {:?}", round, e);
+        }
+    }
+
+    async fn announce_tip(&self, round: Round) -> io::Result<()> {
+        let mut chain_tips = CHAIN_TIPS.lock().await;
+        let tip = chain_tips.take_unannounced();
+        drop(chain_tips);
+
+        let tip = match tip {
+            Some(tip) => tip,
+            None => return Ok(()),
+        };
+        let encoded_tip = serde_json::to_string(&tip)?;
+
+        let manager_agreement = MANAGER_AGREEMENT.lock().await;
+        let interpreter_construct = build_signed(
+            &manager_agreement,
+            task_codes::TIP,
+            round,
+            ("tip".to_string(), tip.epoch.to_string(), encoded_tip),
+        )?;
+        drop(manager_agreement);
+
+        interpreter_construct.broadcast().await
+    }
+
+    async fn agreement_round(&self, round: Round) {
+        let view_timeout = Duration::from_millis(self.view_timeout_ms as u64);
+        task::spawn(async move {
+            loop {
+                if let Err(e) = propose_epoch(round).await {
+                    println!("Failed to propose block order for {}: {:?}", round, e);
+                }
+                sleep(view_timeout).await;
+
+                match advance_view(round).await {
+                    Ok(true) => continue,
+                    Ok(false) => break,
+                    Err(e) => {
+                        println!("Failed to change view for {}: {:?}", round, e);
+                        continue;
+                    }
+                }
+            }
+        });
     }
 }
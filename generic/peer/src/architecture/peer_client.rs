@@ -2,9 +2,12 @@ use std::io::ErrorKind;
 use crate::communication::listener::init_listener;
 use crate::memory::manager_client::MANAGER_CLIENT;
 use crate::memory::manager_server::MANAGER_SERVER;
+use crate::memory::manager_network::MANAGER_NETWORK;
 use crate::utils::aes_encryption::AesEncryption;
 use crate::utils::rsa_encryption::RsaEncryption;
 use common::logger::Logger;
+use common::memory::clock_skew::CLOCK_SKEW;
+use chrono::Utc;
 use tokio::io;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
@@ -17,7 +20,7 @@ pub async fn start_client(
 ) -> io::Result<()> {
     let mut socket = TcpStream::connect(&client_address).await?;
 
-    process_handshake(
+    let (offset_ms, rtt_ms, public_key) = process_handshake(
         &mut socket,
         out_server_url,
         aes_encryption.get_key(),
@@ -25,6 +28,21 @@ pub async fn start_client(
     )
     .await?;
 
+    let mut clock_skew = CLOCK_SKEW.lock().await;
+    clock_skew.record(client_address.to_string(), offset_ms, rtt_ms);
+    drop(clock_skew);
+
+    let mut manager_network = MANAGER_NETWORK.lock().await;
+    let bound = manager_network.bind_key(client_address.to_string(), public_key, false);
+    drop(manager_network);
+    if !bound {
+        Logger::console(
+            "client",
+            &format!("{} presented a key that differs from its genesis key", client_address),
+        );
+        return Err(io::Error::new(ErrorKind::PermissionDenied, "Public key mismatch"));
+    }
+
     // Logger::console(
     //     "client",
     //     &format!("Accepted connection from {}", client_address),
@@ -54,7 +72,7 @@ async fn process_handshake(
     server_address: &str,
     key: &[u8; 32],
     iv: &[u8; 16],
-) -> io::Result<()> {
+) -> io::Result<(i64, i64, String)> {
     let signal = stream.read_u8().await?;
     if signal == 2 {
         return Err(io::Error::new(ErrorKind::Other, "Node full"));
@@ -87,7 +105,16 @@ async fn process_handshake(
         .await?;
     stream.write_all(&encrypted_server_address).await?;
 
+    let sent_at = Utc::now().timestamp_millis();
+    stream.write_i64(sent_at).await?;
     stream.flush().await?;
 
-    Ok(())
+    let peer_time = stream.read_i64().await?;
+    let received_at = Utc::now().timestamp_millis();
+
+    Ok((
+        peer_time - (sent_at + received_at) / 2,
+        received_at - sent_at,
+        String::from_utf8_lossy(&buffer[..bytes_read]).to_string(),
+    ))
 }
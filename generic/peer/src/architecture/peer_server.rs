@@ -3,6 +3,7 @@ use std::sync::Arc;
 use tokio::io::{self, AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::Notify;
+use chrono::Utc;
 
 use crate::memory::manager_client::MANAGER_CLIENT;
 use crate::memory::manager_server::MANAGER_SERVER;
@@ -133,5 +134,9 @@ async fn process_handshake(
     }
     let url = rsa_encryption.decrypt(&encrypted_url[..url_bytes_read]);
 
+    let _ = stream.read_i64().await?;
+    stream.write_i64(Utc::now().timestamp_millis()).await?;
+    stream.flush().await?;
+
     Ok((String::from_utf8_lossy(&url).to_string(), key, iv))
 }
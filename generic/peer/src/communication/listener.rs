@@ -7,6 +7,8 @@ use crate::utils::aes_encryption::AesEncryption;
 use crate::administrator::interpreter::InterpreterConstruct;
 use crate::memory::manager_client::MANAGER_CLIENT;
 use crate::memory::manager_server::MANAGER_SERVER;
+use crate::memory::manager_network::MANAGER_NETWORK;
+use common::logger::Logger;
 
 pub async fn init_listener(aes_encryption: &AesEncryption, client_address: &str, out_server_url: &str, max_connections: u32) {
     let encryption_clone = aes_encryption.clone();
@@ -54,7 +56,16 @@ async fn event_loop(encryption: &AesEncryption, client_address: &str, out_server
             continue;
         }
         let apply_construct = apply_construct?;
-        
+
+        let manager_network = MANAGER_NETWORK.lock().await;
+        let current_round = manager_network.get_round();
+        let slots_per_epoch = manager_network.get_slots_per_epoch();
+        drop(manager_network);
+        if let Err(e) = apply_construct.check_round(current_round, slots_per_epoch) {
+            Logger::console("listener", &format!("Rejected from {}: {}", apply_construct.creator, e));
+            continue;
+        }
+
         let mut distribute_construct = apply_construct.clone();
         distribute_construct.from = out_server_url.to_string();
 
@@ -1,11 +1,19 @@
-use crate::administrator::background::{run_constructor_job, run_memory_jobs};
+use crate::administrator::background::{run_chain_sync_job, run_constructor_job, run_heartbeat_job, run_memory_jobs, run_mempool_job};
 use crate::administrator::synchronizer::Synchronizer;
 use crate::architecture::peer_server::start_server;
+use crate::memory::manager_agreement::MANAGER_AGREEMENT;
+use crate::memory::manager_network::MANAGER_NETWORK;
+use crate::policy::stake_policy;
 use crate::utils::aes_encryption::AesEncryption;
 use crate::utils::rsa_encryption::RsaEncryption;
+use common::config::genesis_config::GenesisConfig;
 use common::config::peer_config::PeerConfig;
+use common::logger::Logger;
+use common::memory::clock_skew::CLOCK_SKEW;
+use common::scheduler::Tick;
+use std::fs;
 use std::sync::Arc;
-use tokio::sync::Notify;
+use tokio::sync::{broadcast, Notify};
 use tokio::task::JoinHandle;
 use tokio::{io, task};
 
@@ -13,9 +21,10 @@ pub mod administrator;
 pub mod architecture;
 pub mod communication;
 pub mod memory;
+pub mod policy;
 pub mod utils;
 
-pub async fn power_module_peer(peer_config: PeerConfig) -> io::Result<Vec<JoinHandle<()>>> {
+pub async fn power_module_peer(peer_config: PeerConfig, genesis_config: GenesisConfig, ticks: broadcast::Receiver<Tick>) -> io::Result<Vec<JoinHandle<()>>> {
     let server_url = format!("{}:{}", &peer_config.host, peer_config.port);
     let out_server_url = if peer_config.out_sub_name != 0 {
         format!(
@@ -26,6 +35,22 @@ pub async fn power_module_peer(peer_config: PeerConfig) -> io::Result<Vec<JoinHa
         format!("{}:{}", &peer_config.out_name, peer_config.out_port)
     };
 
+    let stake_policy = stake_policy::from_config(&peer_config.stake_policy)?;
+    let mut manager_network = MANAGER_NETWORK.lock().await;
+    manager_network.set_policy(stake_policy);
+    if !genesis_config.validator_keys_path.is_empty() {
+        for validator in &genesis_config.validators {
+            let public_key = fs::read_to_string(format!("{}/{}.pem", genesis_config.validator_keys_path, validator))?;
+            RsaEncryption::parse_public(public_key.as_bytes())?;
+            manager_network.bind_key(validator.clone(), public_key, true);
+        }
+    }
+    drop(manager_network);
+
+    let mut clock_skew = CLOCK_SKEW.lock().await;
+    clock_skew.set_tolerance(peer_config.max_clock_skew_ms as i64);
+    drop(clock_skew);
+
     let rsa_encryption = RsaEncryption::new(&peer_config.keys_path);
     let aes_encryption = AesEncryption::new();
 
@@ -38,11 +63,31 @@ pub async fn power_module_peer(peer_config: PeerConfig) -> io::Result<Vec<JoinHa
     let synchronizer_server_url = out_server_url.clone();
     let synchronizer_committee_size = peer_config.committee_size.clone();
     let synchronizer_max_connections = peer_config.peer_connections.clone();
+    let synchronizer_max_batch_blocks = peer_config.max_batch_blocks;
+    let synchronizer_max_batch_bytes = peer_config.max_batch_bytes;
+    let synchronizer_view_timeout_ms = peer_config.view_timeout_ms;
+    let synchronizer_public_key = String::from_utf8_lossy(&rsa_encryption.get_public_pkey()).to_string();
+
+    let mut manager_agreement = MANAGER_AGREEMENT.lock().await;
+    manager_agreement.init(
+        out_server_url.clone(),
+        rsa_encryption.clone(),
+        genesis_config.validators.clone(),
+    );
+    if genesis_config.validators.is_empty() {
+        Logger::console(
+            "agreement",
+            "No genesis validators configured, agreeing with the locally seen committee",
+        );
+    }
+    drop(manager_agreement);
 
     let constructor_aes_encryption = aes_encryption.clone();
     let constructor_peer_config = peer_config.clone();
     let constructor_out_server_url = out_server_url.clone();
 
+    let heartbeat_out_server_url = out_server_url.clone();
+
     let notify = Arc::new(Notify::new());
     let module_task_notify = notify.clone();
     let module_task = task::spawn(async move {
@@ -63,10 +108,14 @@ pub async fn power_module_peer(peer_config: PeerConfig) -> io::Result<Vec<JoinHa
         synchronizer_server_url,
         synchronizer_committee_size,
         synchronizer_max_connections,
+        synchronizer_max_batch_blocks,
+        synchronizer_max_batch_bytes,
+        synchronizer_view_timeout_ms,
+        synchronizer_public_key,
     )
     .await;
     let synchronizer_task = task::spawn(async move {
-        synchronizer.power_synchronizer().await;
+        synchronizer.power_synchronizer(ticks).await;
     });
 
     let constructor_task = task::spawn(async move {
@@ -80,10 +129,19 @@ pub async fn power_module_peer(peer_config: PeerConfig) -> io::Result<Vec<JoinHa
 
     let memory_task = task::spawn(run_memory_jobs());
 
+    let heartbeat_task = task::spawn(run_heartbeat_job(heartbeat_out_server_url));
+
+    let chain_sync_task = task::spawn(run_chain_sync_job());
+
+    let mempool_task = task::spawn(run_mempool_job());
+
     Ok(vec![
         module_task,
         synchronizer_task,
         constructor_task,
         memory_task,
+        heartbeat_task,
+        chain_sync_task,
+        mempool_task,
     ])
 }
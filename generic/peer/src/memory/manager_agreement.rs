@@ -0,0 +1,251 @@
+use common::certificate::{QuorumCertificate, Vote};
+use common::memory::db_queue::{EpochHeader, OrderedBlock};
+use common::round::Round;
+use once_cell::sync::Lazy;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use crate::utils::rsa_encryption::RsaEncryption;
+
+pub type Proposal = (Vec<OrderedBlock>, Vec<String>);
+
+const COMMITTEE_HISTORY: usize = 1024;
+
+pub struct ManagerAgreement {
+    server_url: String,
+    signer: Option<RsaEncryption>,
+    genesis_validators: BTreeSet<String>,
+
+    epoch: u64,
+    view: u64,
+    validators: BTreeSet<String>,
+    history: BTreeMap<u64, BTreeSet<String>>,
+    proposals: HashMap<String, (Proposal, String, String)>,
+    proposed: HashMap<u64, String>,
+    prevotes: HashMap<(u64, String), BTreeSet<String>>,
+    precommits: HashMap<(u64, String), BTreeMap<String, (Round, String)>>,
+    seen: HashSet<(u16, String, u64)>,
+
+    prevoted: bool,
+    precommitted: bool,
+    locked: Option<String>,
+    decided: Option<String>,
+}
+
+impl ManagerAgreement {
+    fn new() -> ManagerAgreement {
+        ManagerAgreement {
+            server_url: String::new(),
+            signer: None,
+            genesis_validators: BTreeSet::new(),
+            epoch: 0,
+            view: 0,
+            validators: BTreeSet::new(),
+            history: BTreeMap::new(),
+            proposals: HashMap::new(),
+            proposed: HashMap::new(),
+            prevotes: HashMap::new(),
+            precommits: HashMap::new(),
+            seen: HashSet::new(),
+            prevoted: false,
+            precommitted: false,
+            locked: None,
+            decided: None,
+        }
+    }
+
+    pub fn init(&mut self, server_url: String, signer: RsaEncryption, genesis_validators: Vec<String>) {
+        self.server_url = server_url;
+        self.signer = Some(signer);
+        self.genesis_validators = genesis_validators.into_iter().collect();
+    }
+
+    pub fn validators_for(&self, committee: BTreeSet<String>) -> BTreeSet<String> {
+        if self.genesis_validators.is_empty() {
+            return committee;
+        }
+        self.genesis_validators.clone()
+    }
+
+    pub fn validators_at(&self, epoch: u64) -> Option<BTreeSet<String>> {
+        if !self.genesis_validators.is_empty() {
+            return Some(self.genesis_validators.clone());
+        }
+        self.history.get(&epoch).cloned()
+    }
+
+    pub fn get_server_url(&self) -> String {
+        self.server_url.clone()
+    }
+
+    pub fn get_signer(&self) -> Option<&RsaEncryption> {
+        self.signer.as_ref()
+    }
+
+    pub fn ensure_epoch(&mut self, epoch: u64, validators: BTreeSet<String>) {
+        if self.epoch == epoch && !self.validators.is_empty() {
+            return;
+        }
+        self.epoch = epoch;
+        self.view = 0;
+        self.history.insert(epoch, validators.clone());
+        while self.history.len() > COMMITTEE_HISTORY {
+            self.history.pop_first();
+        }
+        self.validators = validators;
+        self.proposals.clear();
+        self.proposed.clear();
+        self.prevotes.clear();
+        self.precommits.clear();
+        self.seen.clear();
+        self.prevoted = false;
+        self.precommitted = false;
+        self.locked = None;
+        self.decided = None;
+    }
+
+    pub fn get_epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn get_view(&self) -> u64 {
+        self.view
+    }
+
+    pub fn advance_view(&mut self) -> u64 {
+        self.view += 1;
+        self.prevoted = false;
+        self.precommitted = false;
+        self.view
+    }
+
+    pub fn leader(&self) -> Option<String> {
+        self.leader_of(self.view)
+    }
+
+    pub fn leader_of(&self, view: u64) -> Option<String> {
+        if self.validators.is_empty() {
+            return None;
+        }
+        let index = (self.epoch.wrapping_add(view) % self.validators.len() as u64) as usize;
+        self.validators.iter().nth(index).cloned()
+    }
+
+    pub fn is_validator(&self, url: &str) -> bool {
+        self.validators.contains(url)
+    }
+
+    pub fn quorum(&self) -> usize {
+        (2 * self.validators.len()) / 3 + 1
+    }
+
+    pub fn mark_seen(&mut self, task: u16, creator: String, view: u64) -> bool {
+        self.seen.insert((task, creator, view))
+    }
+
+    pub fn has_seen(&self, task: u16, creator: &str, view: u64) -> bool {
+        self.seen.contains(&(task, creator.to_string(), view))
+    }
+
+    pub fn add_proposal(&mut self, view: u64, digest: String, proposal: Proposal, proposer: String, signature: String) {
+        self.proposed.entry(view).or_insert(digest.clone());
+        self.proposals.entry(digest).or_insert((proposal, proposer, signature));
+    }
+
+    pub fn has_proposal(&self, digest: &str) -> bool {
+        self.proposals.contains_key(digest)
+    }
+
+    pub fn proposal_at(&self, view: u64) -> Option<(String, Proposal)> {
+        let digest = self.proposed.get(&view)?;
+        let (proposal, _, _) = self.proposals.get(digest)?;
+        Some((digest.clone(), proposal.clone()))
+    }
+
+    pub fn get_locked(&self) -> Option<(String, Proposal)> {
+        let digest = self.locked.as_ref()?;
+        let (proposal, _, _) = self.proposals.get(digest)?;
+        Some((digest.clone(), proposal.clone()))
+    }
+
+    pub fn may_prevote(&self, digest: &str, endorsed: bool) -> bool {
+        match &self.locked {
+            Some(locked) => locked == digest,
+            None => endorsed,
+        }
+    }
+
+    pub fn add_prevote(&mut self, view: u64, digest: String, voter: String) {
+        self.prevotes.entry((view, digest)).or_default().insert(voter);
+    }
+
+    pub fn add_precommit(&mut self, view: u64, digest: String, voter: String, round: Round, signature: String) {
+        self.precommits.entry((view, digest)).or_default().insert(voter, (round, signature));
+    }
+
+    pub fn take_prevote(&mut self) -> bool {
+        let first = !self.prevoted;
+        self.prevoted = true;
+        first
+    }
+
+    pub fn take_precommit(&mut self, digest: &str) -> bool {
+        let first = !self.precommitted;
+        self.precommitted = true;
+        if first {
+            self.locked = Some(digest.to_string());
+        }
+        first
+    }
+
+    pub fn prevote_quorum(&self) -> Option<String> {
+        let quorum = self.quorum();
+        self.prevotes
+            .iter()
+            .find(|((view, digest), voters)| {
+                *view == self.view && voters.len() >= quorum && self.proposals.contains_key(digest)
+            })
+            .map(|((_, digest), _)| digest.clone())
+    }
+
+    pub fn precommit_quorum(&self) -> Option<(u64, String)> {
+        let quorum = self.quorum();
+        self.precommits
+            .iter()
+            .find(|((_, digest), voters)| voters.len() >= quorum && self.proposals.contains_key(digest))
+            .map(|((view, digest), _)| (*view, digest.clone()))
+    }
+
+    pub fn decide(&mut self, view: u64, digest: String) -> Option<(Vec<OrderedBlock>, EpochHeader)> {
+        if self.decided.is_some() {
+            return None;
+        }
+        let ((blocks, members), proposer, signature) = self.proposals.get(&digest).cloned()?;
+        let votes: Vec<Vote> = self
+            .precommits
+            .get(&(view, digest.clone()))?
+            .iter()
+            .map(|(voter, (round, signature))| Vote {
+                voter: voter.clone(),
+                round: *round,
+                signature: signature.clone(),
+            })
+            .collect();
+        let certificate = QuorumCertificate {
+            epoch: self.epoch,
+            view,
+            digest: digest.clone(),
+            votes,
+        };
+        self.decided = Some(digest);
+
+        Some((blocks, (proposer, signature, members, Some(certificate))))
+    }
+
+    pub fn is_decided(&self) -> bool {
+        self.decided.is_some()
+    }
+}
+
+pub static MANAGER_AGREEMENT: Lazy<Arc<Mutex<ManagerAgreement>>> =
+    Lazy::new(|| Arc::new(Mutex::new(ManagerAgreement::new())));
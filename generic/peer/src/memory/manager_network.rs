@@ -1,16 +1,48 @@
-use std::cmp::max;
 use once_cell::sync::Lazy;
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 use base64::{engine::general_purpose, Engine};
+use common::memory::last_hash::genesis_hash;
+use common::round::Round;
+use crate::utils::vrf;
+use crate::policy::stake_policy::{DefaultStakePolicy, StakePolicy, StakeRecord};
+use common::memory::stake_table::{CommitteeRecord, StakeEntry, StakeSnapshot};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NodeInfo {
     stake: i32,
     reward: i32,
     penalty: i32,
+
+    #[serde(default)]
+    public_key: String,
+
+    #[serde(default)]
+    slashed: BTreeSet<Round>,
+}
+
+impl NodeInfo {
+    fn record(&self) -> StakeRecord {
+        StakeRecord {
+            stake: self.stake,
+            reward: self.reward,
+            penalty: self.penalty,
+        }
+    }
+
+    fn set_record(&mut self, record: StakeRecord) {
+        self.stake = record.stake;
+        self.reward = record.reward;
+        self.penalty = record.penalty;
+    }
+}
+
+const SEED_HISTORY: u64 = 4;
+
+fn default_stake_policy() -> Arc<dyn StakePolicy> {
+    Arc::new(DefaultStakePolicy::default())
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,35 +53,33 @@ pub struct ManagerNetwork {
     maintain: HashSet<String>,
 
     #[serde(skip)]
-    committee: BTreeSet<String>,
+    pinned: HashSet<String>,
 
     #[serde(skip)]
-    received: BTreeSet<String>,
+    committee: BTreeMap<Round, BTreeSet<String>>,
 
-    #[serde(serialize_with = "serialize_seed", deserialize_with = "deserialize_seed")]
-    seed: [u8; 32],
-}
+    #[serde(skip)]
+    received: BTreeMap<Round, BTreeSet<String>>,
 
-fn serialize_seed<S>(seed: &[u8; 32], serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: serde::Serializer,
-{
-    let encoded = general_purpose::STANDARD.encode(seed);
-    serializer.serialize_str(&encoded)
-}
+    #[serde(skip)]
+    blocks: BTreeMap<Round, BTreeMap<String, String>>,
+
+    #[serde(skip)]
+    committee_size: u32,
+
+    #[serde(skip)]
+    committee_round: Round,
 
-fn deserialize_seed<'de, D>(deserializer: D) -> Result<[u8; 32], D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    let s = String::deserialize(deserializer)?;
-    let bytes = general_purpose::STANDARD
-        .decode(&s)
-        .map_err(serde::de::Error::custom)?;
-    let array: [u8; 32] = bytes
-        .try_into()
-        .map_err(|_| serde::de::Error::custom("Invalid length for seed"))?;
-    Ok(array)
+    #[serde(skip)]
+    seeds: BTreeMap<u64, [u8; 32]>,
+
+    round: Round,
+
+    #[serde(skip)]
+    slots_per_epoch: u64,
+
+    #[serde(skip, default = "default_stake_policy")]
+    policy: Arc<dyn StakePolicy>,
 }
 
 impl ManagerNetwork {
@@ -57,9 +87,16 @@ impl ManagerNetwork {
         ManagerNetwork {
             nodes: BTreeMap::new(),
             maintain: HashSet::new(),
-            committee: BTreeSet::new(),
-            received: BTreeSet::new(),
-            seed: *blake3::hash("DefaultSeed".as_bytes()).as_bytes(),
+            pinned: HashSet::new(),
+            committee: BTreeMap::new(),
+            received: BTreeMap::new(),
+            blocks: BTreeMap::new(),
+            committee_size: 0,
+            committee_round: Round::default(),
+            seeds: BTreeMap::new(),
+            round: Round::default(),
+            slots_per_epoch: 0,
+            policy: default_stake_policy(),
         }
     }
 
@@ -68,94 +105,174 @@ impl ManagerNetwork {
     }
 
 
-    pub fn insert_node(&mut self, url: String, stake: i32, reward: i32, penalty: i32) {
+    pub fn insert_node(&mut self, url: String, stake: i32, reward: i32, penalty: i32, public_key: String) {
         self.nodes.insert(url.clone(), NodeInfo {
             stake,
             reward,
             penalty,
+            public_key,
+            slashed: BTreeSet::new(),
         });
     }
 
+    pub fn bind_key(&mut self, url: String, public_key: String, pinned: bool) -> bool {
+        if self.pinned.contains(&url) && !pinned {
+            return self.nodes.get(&url).map(|node_info| node_info.public_key == public_key).unwrap_or(false);
+        }
+        if pinned {
+            self.pinned.insert(url.clone());
+        }
+        match self.nodes.get_mut(&url) {
+            Some(node_info) => node_info.public_key = public_key,
+            None => {
+                let initial_stake = self.initial_stake();
+                self.insert_node(url, initial_stake, 0, 0, public_key);
+            }
+        }
+        true
+    }
+
     pub fn perform_maintenance(&mut self, received_network: ManagerNetwork) {
-        for (id, received_node) in received_network.nodes {
+        for (id, mut received_node) in received_network.nodes {
             match self.nodes.get_mut(&id) {
                 Some(node_info) => {
-                    node_info.stake = max(node_info.stake, received_node.stake);
-                    node_info.reward = max(node_info.reward, received_node.reward);
-                    node_info.penalty = max(node_info.penalty, received_node.penalty);
+                    let merged = self.policy.merge(node_info.record(), received_node.record());
+                    node_info.set_record(merged);
                 }
                 None => {
+                    received_node.public_key = String::new();
+                    received_node.slashed.clear();
                     self.nodes.insert(id, received_node);
                 }
             }
         }
 
-        self.seed = max(self.seed, received_network.seed);
     }
 
-    pub fn choose_committee(&mut self, size: u32) {
-        self.committee.clear();
+    pub fn set_committee_size(&mut self, size: u32) {
+        self.committee_size = size;
+    }
 
-        let total_stake: i32 = self.nodes.iter().map(|(_, s)| s.stake).sum();
+    pub fn set_seed(&mut self, epoch: u64, tip_hash: &str) {
+        self.seeds.insert(epoch, *blake3::hash(tip_hash.as_bytes()).as_bytes());
+        self.seeds.retain(|seeded_epoch, _| *seeded_epoch + SEED_HISTORY >= epoch);
+    }
 
-        let mut candidates: Vec<(String, f64)> = self.nodes.iter().map(|(id, peer)| {
-            let mut hasher = blake3::Hasher::new();
-            hasher.update(&self.seed);
-            hasher.update(id.as_bytes());
-            let hash = hasher.finalize();
+    fn seed_of(&self, epoch: u64) -> [u8; 32] {
+        match self.seeds.get(&epoch) {
+            Some(seed) => *seed,
+            None => *blake3::hash(genesis_hash().as_bytes()).as_bytes(),
+        }
+    }
 
-            let hash_value = u64::from_le_bytes(hash.as_bytes()[..8].try_into().unwrap());
-            let normalized = (hash_value as f64) / (u64::MAX as f64);
+    pub fn vrf_input(&self, round: Round) -> Vec<u8> {
+        let mut input = self.seed_of(round.epoch).to_vec();
+        input.extend_from_slice(&round.epoch.to_le_bytes());
+        input.extend_from_slice(&round.slot.to_le_bytes());
+        input
+    }
 
-            let threshold = (peer.stake as f64) / (total_stake as f64);
-            let score = normalized / threshold;
+    pub fn open_committee(&mut self, round: Round) {
+        self.committee.retain(|member_round, _| *member_round >= round);
+        self.blocks.retain(|block_round, _| *block_round >= round);
+        self.received.retain(|received_round, _| *received_round >= round);
+        self.committee_round = round;
+    }
 
-            (id.clone(), score)
-        }).collect();
+    pub fn is_eligible(&self, url: &str, output: &[u8; 32]) -> bool {
+        let total_stake: i32 = self.nodes.values().map(|peer| self.effective_stake(peer)).sum();
+        let stake = match self.nodes.get(url) {
+            Some(peer) => self.effective_stake(peer),
+            None => return false,
+        };
+        if total_stake <= 0 {
+            return false;
+        }
 
-        candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        let hash_value = u64::from_le_bytes(output[..8].try_into().unwrap());
+        let normalized = (hash_value as f64) / (u64::MAX as f64);
+        let threshold = (self.committee_size as f64) * (stake as f64) / (total_stake as f64);
 
-        for (id, _) in candidates.into_iter().take(size as usize) {
-            self.committee.insert(id);
+        normalized < threshold
+    }
+
+    pub fn check_member(&self, url: &str, round: Round, proof: &str) -> bool {
+        let public_key = match self.get_public_key(url) {
+            Some(public_key) => public_key,
+            None => return false,
+        };
+        match vrf::verify(&public_key, &self.vrf_input(round), proof) {
+            Some(output) => self.is_eligible(url, &output),
+            None => false,
         }
+    }
 
-        self.refresh_stakes();
-        self.update_seed();
-        self.received.clear();
+    pub fn admit_member(&mut self, url: String, round: Round) {
+        self.committee.entry(round).or_default().insert(url);
     }
-    
-    fn refresh_stakes(&mut self) {
-        let committee_size      = self.committee.len().max(1);
-        let total_reward_pool   = 30;
-        let total_penalty_pool  = 50;
 
-        let base_reward  = total_reward_pool  / committee_size;
-        let base_penalty = total_penalty_pool / committee_size;
+    pub fn record_block(&mut self, url: String, round: Round, encoded_block: String) {
+        self.blocks.entry(round).or_default().insert(url, encoded_block);
+    }
 
-        for (url, peer) in self.nodes.iter_mut() {
-            let (new_reward, new_penalty) = if self.committee.contains(url) {
-                (0, peer.penalty + base_penalty as i32)
-            } else {
-                (peer.reward + base_reward as i32, 0)
-            };
-
-            let mut new_stake = peer.stake + new_reward - new_penalty;
-            new_stake = new_stake.clamp(1, 100);
-
-            peer.reward  = new_reward;
-            peer.penalty = new_penalty;
-            peer.stake   = new_stake;
+    pub fn get_block(&self, url: &str, round: Round) -> Option<String> {
+        self.blocks.get(&round).and_then(|blocks| blocks.get(url)).cloned()
+    }
+
+    pub fn slash(&mut self, url: &str, round: Round) -> bool {
+        let node_info = match self.nodes.get_mut(url) {
+            Some(node_info) => node_info,
+            None => return false,
+        };
+        if !node_info.slashed.insert(round) {
+            return false;
         }
+        if let Some(members) = self.committee.get_mut(&round) {
+            members.remove(url);
+        }
+        true
+    }
+
+    pub fn is_slashed(&self, url: &str, round: Round) -> bool {
+        self.nodes
+            .get(url)
+            .map(|node_info| node_info.slashed.contains(&round))
+            .unwrap_or(false)
     }
 
-    fn update_seed(&mut self) {
-        let mut hasher = blake3::Hasher::new();
+    pub fn refresh_stakes(&mut self, members: &BTreeSet<String>) {
+        let committee_size = members.len();
 
-        for url in &self.committee {
-            hasher.update(url.clone().as_bytes());
+        for (url, peer) in self.nodes.iter_mut() {
+            let is_member = members.contains(url);
+            let refreshed = self.policy.refresh(peer.record(), is_member, committee_size);
+            peer.set_record(refreshed);
         }
+    }
+
+    fn effective_stake(&self, peer: &NodeInfo) -> i32 {
+        self.policy.effective_stake(peer.record(), peer.slashed.len())
+    }
+
+    pub fn set_policy(&mut self, policy: Arc<dyn StakePolicy>) {
+        self.policy = policy;
+    }
+
+    pub fn initial_stake(&self) -> i32 {
+        self.policy.initial_stake()
+    }
 
-        self.seed = *hasher.finalize().as_bytes();
+    pub fn set_round(&mut self, round: Round, slots_per_epoch: u64) {
+        self.round = round;
+        self.slots_per_epoch = slots_per_epoch;
+    }
+
+    pub fn get_round(&self) -> Round {
+        self.round
+    }
+
+    pub fn get_slots_per_epoch(&self) -> u64 {
+        self.slots_per_epoch
     }
 
     pub fn prepare_for_maintenance(&mut self) {
@@ -170,11 +287,11 @@ impl ManagerNetwork {
         self.maintain.contains(from)
     }
 
-    pub fn mark_received(&mut self, node_url: String) {
-        self.received.insert(node_url);
+    pub fn mark_received(&mut self, node_url: String, round: Round) {
+        self.received.entry(round).or_default().insert(node_url);
     }
-    pub fn has_received(&self, node_url: &str) -> bool {
-        self.received.contains(node_url)
+    pub fn has_received(&self, node_url: &str, round: Round) -> bool {
+        self.received.get(&round).map(|received| received.contains(node_url)).unwrap_or(false)
     }
 
     pub fn serialize(&self) -> String {
@@ -185,18 +302,65 @@ impl ManagerNetwork {
         self.nodes.contains_key(url)
     }
 
-    pub fn missing_nodes(&self) -> BTreeSet<String> {
-        &self.committee - &self.received
+    pub fn unknown_members(&self, known: &BTreeSet<String>) -> BTreeSet<String> {
+        &self.get_committee() - known
+    }
+
+    pub fn get_committee_round(&self) -> Round {
+        self.committee_round
     }
 
     pub fn get_committee(&self) -> BTreeSet<String> {
-        self.committee.clone()
+        self.committee.get(&self.committee_round).cloned().unwrap_or_default()
     }
 
     pub fn get_all_nodes(&self) -> Vec<(String, i32)> {
         self.nodes.iter().map(|(url, peer)| {(url.clone(), peer.stake.clone())}).collect()
     }
 
+    pub fn get_public_key(&self, url: &str) -> Option<String> {
+        self.nodes
+            .get(url)
+            .map(|peer| peer.public_key.clone())
+            .filter(|public_key| !public_key.is_empty())
+    }
+
+    pub fn snapshot(&self) -> StakeSnapshot {
+        StakeSnapshot {
+            nodes: self.nodes.iter().map(|(url, peer)| StakeEntry {
+                url: url.clone(),
+                stake: peer.stake,
+                reward: peer.reward,
+                penalty: peer.penalty,
+                public_key: peer.public_key.clone(),
+                slashed: peer.slashed.iter().cloned().collect(),
+            }).collect(),
+            seed: general_purpose::STANDARD.encode(self.seed_of(self.round.epoch)),
+        }
+    }
+
+    pub fn committee_record(&self) -> CommitteeRecord {
+        CommitteeRecord {
+            round: self.committee_round,
+            members: self.get_committee().into_iter().collect(),
+            seed: general_purpose::STANDARD.encode(self.seed_of(self.committee_round.epoch)),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: StakeSnapshot) {
+        for entry in snapshot.nodes {
+            let public_key = self
+                .nodes
+                .get(&entry.url)
+                .map(|node_info| node_info.public_key.clone())
+                .unwrap_or_default();
+            self.insert_node(entry.url.clone(), entry.stake, entry.reward, entry.penalty, public_key);
+            if let Some(node_info) = self.nodes.get_mut(&entry.url) {
+                node_info.slashed.extend(entry.slashed);
+            }
+        }
+    }
+
     pub fn remove_node(&mut self, url: &str) {
         self.nodes.remove(url);
     }
@@ -1,3 +1,4 @@
 pub mod manager_server;
 pub mod manager_client;
-pub mod manager_network;
\ No newline at end of file
+pub mod manager_network;
+pub mod manager_agreement;
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod stake_policy;
\ No newline at end of file
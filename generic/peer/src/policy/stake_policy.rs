@@ -0,0 +1,104 @@
+use common::config::stake_policy_config::StakePolicyConfig;
+use std::fmt::Debug;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+use tokio::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StakeRecord {
+    pub stake: i32,
+    pub reward: i32,
+    pub penalty: i32,
+}
+
+pub trait StakePolicy: Debug + Send + Sync {
+    fn initial_stake(&self) -> i32;
+
+    fn effective_stake(&self, record: StakeRecord, slashed: usize) -> i32;
+
+    fn refresh(&self, record: StakeRecord, is_member: bool, committee_size: usize) -> StakeRecord;
+
+    fn merge(&self, local: StakeRecord, received: StakeRecord) -> StakeRecord;
+}
+
+#[derive(Debug, Clone)]
+pub struct DefaultStakePolicy {
+    initial_stake: i32,
+    min_stake: i32,
+    max_stake: i32,
+    reward_pool: i32,
+    penalty_pool: i32,
+    slash_penalty: i32,
+    penalize_committee: bool,
+}
+
+impl DefaultStakePolicy {
+    pub fn new(config: &StakePolicyConfig) -> DefaultStakePolicy {
+        DefaultStakePolicy {
+            initial_stake: config.initial_stake,
+            min_stake: config.min_stake,
+            max_stake: config.max_stake,
+            reward_pool: config.reward_pool,
+            penalty_pool: config.penalty_pool,
+            slash_penalty: config.slash_penalty,
+            penalize_committee: config.penalize_committee,
+        }
+    }
+}
+
+impl Default for DefaultStakePolicy {
+    fn default() -> Self {
+        DefaultStakePolicy {
+            initial_stake: 50,
+            min_stake: 1,
+            max_stake: 100,
+            reward_pool: 30,
+            penalty_pool: 50,
+            slash_penalty: 25,
+            penalize_committee: true,
+        }
+    }
+}
+
+impl StakePolicy for DefaultStakePolicy {
+    fn initial_stake(&self) -> i32 {
+        self.initial_stake
+    }
+
+    fn effective_stake(&self, record: StakeRecord, slashed: usize) -> i32 {
+        (record.stake - self.slash_penalty * slashed as i32).max(0)
+    }
+
+    fn refresh(&self, record: StakeRecord, is_member: bool, committee_size: usize) -> StakeRecord {
+        let committee_size = committee_size.max(1) as i32;
+        let base_reward  = self.reward_pool  / committee_size;
+        let base_penalty = self.penalty_pool / committee_size;
+
+        let (reward, penalty) = if is_member == self.penalize_committee {
+            (0, record.penalty + base_penalty)
+        } else {
+            (record.reward + base_reward, 0)
+        };
+
+        StakeRecord {
+            stake: (record.stake + reward - penalty).clamp(self.min_stake, self.max_stake),
+            reward,
+            penalty,
+        }
+    }
+
+    fn merge(&self, local: StakeRecord, received: StakeRecord) -> StakeRecord {
+        StakeRecord {
+            stake: local.stake.max(received.stake),
+            reward: local.reward.max(received.reward),
+            penalty: local.penalty.max(received.penalty),
+        }
+    }
+}
+
+pub fn from_config(config: &StakePolicyConfig) -> io::Result<Arc<dyn StakePolicy>> {
+    match config.kind.as_str() {
+        "default" => Ok(Arc::new(DefaultStakePolicy::new(config))),
+        kind => Err(Error::new(ErrorKind::InvalidInput, format!("Unknown stake policy {}", kind))),
+    }
+}
\ No newline at end of file
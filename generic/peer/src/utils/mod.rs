@@ -1,4 +1,4 @@
 pub mod task_codes;
 pub mod rsa_encryption;
 pub mod aes_encryption;
-
+pub mod vrf;
\ No newline at end of file
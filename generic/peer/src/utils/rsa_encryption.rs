@@ -1,5 +1,7 @@
 use std::fs;
 use openssl::encrypt::{Decrypter, Encrypter};
+use openssl::hash::MessageDigest;
+use openssl::sign::{Signer, Verifier};
 use openssl::pkey::{PKey, Public};
 use openssl::rsa::Rsa;
 use tokio::io;
@@ -54,6 +56,24 @@ impl RsaEncryption {
         decrypted
     }
 
+    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
+        let mut signer = Signer::new(MessageDigest::sha256(), &self.private_pkey).expect("Failed to create signer");
+        signer.update(message).expect("Signing failed");
+        signer.sign_to_vec().expect("Signing failed")
+    }
+
+    pub fn verify(message: &[u8], signature: &[u8], pkey: &PKey<Public>) -> bool {
+        let verifier = Verifier::new(MessageDigest::sha256(), pkey);
+        if verifier.is_err() {
+            return false;
+        }
+        let mut verifier = verifier.unwrap();
+        if verifier.update(message).is_err() {
+            return false;
+        }
+        verifier.verify(signature).unwrap_or(false)
+    }
+
     pub fn get_public_pkey(&self) -> Vec<u8> {
         self.public_pkey.public_key_to_pem().unwrap()
     }
@@ -1,11 +1,21 @@
 pub const NONE: u16 = 0;
 
 pub const MAINTAIN: u16 = 100;
+pub const TIP: u16 = 101;
 
 pub const BLOCK: u16 = 200;
 pub const SYNC: u16 = 201;
+pub const HEARTBEAT: u16 = 202;
+pub const PROPOSE: u16 = 203;
+pub const PREVOTE: u16 = 204;
+pub const PRECOMMIT: u16 = 205;
+pub const CHAIN_REQ: u16 = 206;
+pub const EVIDENCE: u16 = 207;
+pub const MEMPOOL: u16 = 208;
 
 pub const RESP_SYNC: u16 = 301;
+pub const RESP_HEARTBEAT: u16 = 302;
+pub const RESP_CHAIN: u16 = 303;
 
 pub const INACTIVE: u16 = 400;
 
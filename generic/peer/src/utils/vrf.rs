@@ -0,0 +1,20 @@
+use crate::utils::rsa_encryption::RsaEncryption;
+use base64::{engine::general_purpose, Engine};
+
+pub fn prove(rsa_encryption: &RsaEncryption, input: &[u8]) -> (String, [u8; 32]) {
+    let proof = rsa_encryption.sign(input);
+    (general_purpose::STANDARD.encode(&proof), output_of(&proof))
+}
+
+pub fn verify(public_pem: &str, input: &[u8], proof: &str) -> Option<[u8; 32]> {
+    let proof = general_purpose::STANDARD.decode(proof).ok()?;
+    let pkey = RsaEncryption::parse_public(public_pem.as_bytes()).ok()?;
+    if !RsaEncryption::verify(input, &proof, &pkey) {
+        return None;
+    }
+    Some(output_of(&proof))
+}
+
+fn output_of(proof: &[u8]) -> [u8; 32] {
+    *blake3::hash(proof).as_bytes()
+}
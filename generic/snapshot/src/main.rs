@@ -0,0 +1,26 @@
+use common::config::AppConfig;
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    let settings = AppConfig::new("./resources")
+        .expect("Failed to load settings");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match args.iter().map(String::as_str).collect::<Vec<&str>>().as_slice() {
+        ["export", path] => db::export_snapshot(&settings.database, path, false).await,
+        ["export", path, "--history"] => db::export_snapshot(&settings.database, path, true).await,
+        ["import", path] => db::import_snapshot(&settings.database, path).await,
+        _ => {
+            eprintln!("Usage: snapshot export <file.ndjson.gz> [--history] | snapshot import <file.ndjson.gz>");
+            std::process::exit(2);
+        }
+    };
+
+    match result {
+        Ok(manifest) => println!("{}", manifest),
+        Err(e) => {
+            eprintln!("{:?}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use common::config::AppConfig;
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 3)]
+async fn main() {
+    let settings = AppConfig::new("./resources")
+        .expect("Failed to load settings");
+    let from = std::env::args().nth(1);
+
+    match db::verify_chain(&settings.database, from).await {
+        Ok((valid, report)) => {
+            println!("{}", report);
+            if !valid {
+                std::process::exit(2);
+            }
+        }
+        Err(e) => {
+            eprintln!("{:?}", e);
+            std::process::exit(1);
+        }
+    }
+}
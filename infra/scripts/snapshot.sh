@@ -0,0 +1,6 @@
+#!/usr/bin/env bash
+
+# shellcheck disable=SC2046
+export $(grep -v '^#' infra/environment/node.env | xargs)
+
+cargo run --package snapshot -- "$@"
\ No newline at end of file
@@ -0,0 +1,7 @@
+#!/usr/bin/env bash
+
+# shellcheck disable=SC2046
+export $(grep -v '^#' infra/environment/node.env | xargs)
+
+echo "Starting chain verification"
+cargo run --package verify -- "$@"
\ No newline at end of file
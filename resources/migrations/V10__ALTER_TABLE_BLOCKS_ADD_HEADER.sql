@@ -0,0 +1,16 @@
+ALTER TABLE blocks
+    ADD COLUMN IF NOT EXISTS height             BIGINT NULL,
+    ADD COLUMN IF NOT EXISTS proposer           TEXT   NULL,
+    ADD COLUMN IF NOT EXISTS proposer_signature TEXT   NULL,
+    ADD COLUMN IF NOT EXISTS committee          TEXT[] NULL;
+
+WITH RECURSIVE chain AS (
+    SELECT hash, 1::BIGINT AS height
+    FROM blocks WHERE previous_hash = 'RGVmYXVsdEhhc2g'
+    UNION ALL
+    SELECT b.hash, chain.height + 1
+    FROM blocks b JOIN chain ON b.previous_hash = chain.hash
+)
+UPDATE blocks SET height = chain.height FROM chain WHERE blocks.hash = chain.hash;
+
+CREATE INDEX IF NOT EXISTS blocks_height_idx ON blocks (height);
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE blocks
+    ADD COLUMN IF NOT EXISTS simple_hash TEXT NULL;
\ No newline at end of file
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS genesis
+(
+    id         SMALLINT PRIMARY KEY,
+    chain_id   TEXT        NOT NULL,
+    hash       TEXT        NOT NULL,
+    validators TEXT[]      NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
\ No newline at end of file
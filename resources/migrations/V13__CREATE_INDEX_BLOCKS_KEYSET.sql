@@ -0,0 +1,3 @@
+CREATE INDEX IF NOT EXISTS blocks_created_at_hash_idx ON blocks (created_at DESC, hash DESC);
+
+CREATE INDEX IF NOT EXISTS blocks_organization_created_at_hash_idx ON blocks (organization, created_at DESC, hash DESC);
\ No newline at end of file
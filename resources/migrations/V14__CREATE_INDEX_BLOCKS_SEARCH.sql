@@ -0,0 +1,5 @@
+CREATE INDEX IF NOT EXISTS blocks_proposer_created_at_hash_idx ON blocks (proposer, created_at DESC, hash DESC);
+
+CREATE INDEX IF NOT EXISTS blocks_organization_height_idx ON blocks (organization, height);
+
+CREATE INDEX IF NOT EXISTS blocks_proposer_height_idx ON blocks (proposer, height);
\ No newline at end of file
@@ -0,0 +1,14 @@
+ALTER TABLE blocks
+    ADD COLUMN IF NOT EXISTS payload_json JSONB NULL;
+
+CREATE OR REPLACE FUNCTION try_parse_jsonb(value TEXT) RETURNS JSONB AS $$
+BEGIN
+    RETURN value::JSONB;
+EXCEPTION WHEN others THEN
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql IMMUTABLE;
+
+UPDATE blocks SET payload_json = try_parse_jsonb(payload) WHERE payload_json IS NULL;
+
+CREATE INDEX IF NOT EXISTS blocks_payload_json_idx ON blocks USING GIN (payload_json jsonb_path_ops);
\ No newline at end of file
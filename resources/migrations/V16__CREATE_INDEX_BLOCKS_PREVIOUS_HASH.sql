@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS blocks_previous_hash_idx ON blocks (previous_hash);
\ No newline at end of file
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS certificates
+(
+    epoch       BIGINT PRIMARY KEY,
+    digest      TEXT   NOT NULL,
+    certificate JSONB  NOT NULL
+);
\ No newline at end of file
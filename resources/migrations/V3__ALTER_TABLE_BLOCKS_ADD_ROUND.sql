@@ -0,0 +1,5 @@
+ALTER TABLE blocks
+    ADD COLUMN IF NOT EXISTS epoch BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS slot  BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS blocks_epoch_slot_idx ON blocks (epoch, slot);
\ No newline at end of file
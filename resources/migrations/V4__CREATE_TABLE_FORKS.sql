@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS forks
+(
+    id           BIGSERIAL PRIMARY KEY,
+    epoch        BIGINT      NOT NULL,
+    peer         TEXT        NOT NULL,
+    local_hash   TEXT        NOT NULL,
+    local_height BIGINT      NOT NULL,
+    peer_hash    TEXT        NOT NULL,
+    peer_height  BIGINT      NOT NULL,
+    local_only   TEXT[]      NOT NULL,
+    peer_only    TEXT[]      NOT NULL,
+    detected_at  TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS forks_detected_at_idx ON forks (detected_at DESC);
\ No newline at end of file
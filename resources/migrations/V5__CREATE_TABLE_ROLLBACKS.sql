@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS rollbacks
+(
+    id              BIGSERIAL PRIMARY KEY,
+    ancestor_hash   TEXT        NOT NULL,
+    peer            TEXT        NOT NULL,
+    reverted_hashes TEXT[]      NOT NULL,
+    applied_hashes  TEXT[]      NOT NULL,
+    reverted_blocks JSONB       NOT NULL,
+    reverted_at     TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS rollbacks_reverted_at_idx ON rollbacks (reverted_at DESC);
\ No newline at end of file
@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS stakes
+(
+    url        TEXT PRIMARY KEY,
+    stake      INTEGER     NOT NULL,
+    reward     INTEGER     NOT NULL,
+    penalty    INTEGER     NOT NULL,
+    public_key TEXT        NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS network_seed
+(
+    id         SMALLINT PRIMARY KEY,
+    seed       TEXT        NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS committees
+(
+    epoch       BIGINT      NOT NULL,
+    slot        BIGINT      NOT NULL,
+    members     TEXT[]      NOT NULL,
+    seed        TEXT        NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (epoch, slot)
+);
\ No newline at end of file
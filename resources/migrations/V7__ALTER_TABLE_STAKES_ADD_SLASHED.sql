@@ -0,0 +1 @@
+ALTER TABLE stakes ADD COLUMN IF NOT EXISTS slashed JSONB NOT NULL DEFAULT '[]'::jsonb;
\ No newline at end of file
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS pending_blocks
+(
+    hash         TEXT PRIMARY KEY,
+    organization TEXT        NOT NULL,
+    payload      TEXT        NOT NULL,
+    feedback     TEXT,
+    full_hash    TEXT,
+    accepted_at  TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS pending_blocks_accepted_at_idx ON pending_blocks (accepted_at);
\ No newline at end of file
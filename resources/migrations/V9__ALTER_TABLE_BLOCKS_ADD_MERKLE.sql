@@ -0,0 +1,5 @@
+ALTER TABLE blocks
+    ADD COLUMN IF NOT EXISTS merkle_root TEXT   NULL,
+    ADD COLUMN IF NOT EXISTS leaf_index  BIGINT NULL;
+
+CREATE INDEX IF NOT EXISTS blocks_merkle_root_idx ON blocks (merkle_root);
\ No newline at end of file